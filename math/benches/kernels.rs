@@ -0,0 +1,90 @@
+// Compare the scalar and `simd` kernels by saving a baseline without the
+// feature and measuring against it with the feature enabled:
+//
+//     cargo bench -p rge-math -- --save-baseline scalar
+//     cargo bench -p rge-math --features simd -- --baseline scalar
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rge_math::{Mat4, Pt3, Quat, SquareMat, Vec3, Vec4, VecSpace};
+
+const N: usize = 1024;
+
+fn matrices() -> Vec<Mat4> {
+    (0..N)
+        .map(|i| {
+            let t = i as f32 * 0.01;
+            let eye = Pt3::new(t.cos() * 5., 1. + t, t.sin() * 5.);
+            Mat4::perspective_rh(1.2, 1.5, 0.1, 100.)
+                * Mat4::look_at_rh(eye, Pt3::new(0., 0., 0.), Vec3::Y)
+        })
+        .collect()
+}
+
+fn vectors() -> Vec<Vec4> {
+    (0..N)
+        .map(|i| {
+            let t = i as f32;
+            Vec4::new(t.sin(), t.cos(), t * 0.5, 1.)
+        })
+        .collect()
+}
+
+fn quats() -> Vec<Quat> {
+    (0..N)
+        .map(|i| {
+            let t = i as f32 * 0.1;
+            Quat::from_axis_angle(Vec3::new(t.sin(), 1., t.cos()).normalize(), t)
+        })
+        .collect()
+}
+
+fn multiply(c: &mut Criterion) {
+    let ms = matrices();
+    let qs = quats();
+    let mut group = c.benchmark_group("multiply");
+    group.throughput(Throughput::Elements(N as u64));
+    group.bench_function("mat4", |bench| {
+        bench.iter(|| {
+            ms.windows(2)
+                .fold(Mat4::IDENTITY, |acc, w| acc * black_box(w[0] * w[1]))
+        })
+    });
+    group.bench_function("quat", |bench| {
+        bench.iter(|| qs.iter().fold(Quat::IDENTITY, |acc, q| acc * black_box(q)))
+    });
+    group.finish();
+}
+
+fn inverse(c: &mut Criterion) {
+    let ms = matrices();
+    let mut group = c.benchmark_group("inverse");
+    group.throughput(Throughput::Elements(N as u64));
+    group.bench_function("mat4", |bench| {
+        bench.iter(|| {
+            ms.iter()
+                .map(|m| SquareMat::invert(black_box(m)))
+                .filter(Option::is_some)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn transform(c: &mut Criterion) {
+    let m = matrices()[N / 2];
+    let vs = vectors();
+    let mut group = c.benchmark_group("transform");
+    group.throughput(Throughput::Elements(N as u64));
+    group.bench_function("mat4_vec4", |bench| {
+        bench.iter(|| {
+            vs.iter()
+                .fold(Vec4::ZERO, |acc, v| acc + black_box(m) * black_box(v))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, multiply, inverse, transform);
+criterion_main!(benches);
@@ -0,0 +1,205 @@
+use crate::{
+    mat3::Mat3, mat4::Mat4, pt3::Pt3, structure::VecSpace, transform4::Transform4, vec3::Vec3,
+};
+
+/// Axis-aligned bounding box. A box with `min > max` on any axis is empty.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Pt3,
+    pub max: Pt3,
+}
+
+impl Aabb {
+    /// Box containing nothing, the identity of `union` and `include`.
+    pub const EMPTY: Self = Self {
+        min: Pt3 {
+            x: f32::INFINITY,
+            y: f32::INFINITY,
+            z: f32::INFINITY,
+        },
+        max: Pt3 {
+            x: f32::NEG_INFINITY,
+            y: f32::NEG_INFINITY,
+            z: f32::NEG_INFINITY,
+        },
+    };
+
+    pub fn new(min: Pt3, max: Pt3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Pt3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// Smallest box containing all `points`, `None` when there are none.
+    pub fn from_points(points: &[Pt3]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        Some(
+            rest.iter()
+                .fold(Self::new(*first, *first), |b, p| b.include(p)),
+        )
+    }
+
+    pub fn center(&self) -> Pt3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        Vec3::from(self.max - self.min) * 0.5
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn corners(&self) -> [Pt3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Pt3::new(a.x, a.y, a.z),
+            Pt3::new(b.x, a.y, a.z),
+            Pt3::new(a.x, b.y, a.z),
+            Pt3::new(b.x, b.y, a.z),
+            Pt3::new(a.x, a.y, b.z),
+            Pt3::new(b.x, a.y, b.z),
+            Pt3::new(a.x, b.y, b.z),
+            Pt3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self::new(self.min.min(&other.min), self.max.max(&other.max))
+    }
+
+    /// Smallest box containing this box and `p`.
+    pub fn include(&self, p: &Pt3) -> Self {
+        Self::new(self.min.min(p), self.max.max(p))
+    }
+
+    /// Grows the box by `margin` on every side, shrinking for negative values.
+    pub fn expand(&self, margin: f32) -> Self {
+        let m = Vec3::new(margin, margin, margin);
+        Self::new(self.min - m, self.max + m)
+    }
+
+    pub fn contains_point(&self, p: &Pt3) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+
+    /// Box around the affine image of this box, see Arvo, "Transforming
+    /// Axis-Aligned Bounding Boxes", Graphics Gems. Empty boxes stay
+    /// `EMPTY`.
+    pub fn transform(&self, m: &Mat4) -> Self {
+        if self.is_empty() {
+            // Warning: the infinite bounds would turn into NaN below
+            return Self::EMPTY;
+        }
+        let l = Mat3::from(m);
+        let e = self.half_extents();
+        let extents = Mat3::from_columns(l.a.abs(), l.b.abs(), l.c.abs()) * e;
+        Self::from_center(
+            Pt3::from(l * Vec3::from(self.center())) + m.get_translation(),
+            extents,
+        )
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = Vec3::from(self.max - self.min);
+        2. * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    /// Point of the box closest to `p`.
+    pub fn closest_point(&self, p: &Pt3) -> Pt3 {
+        p.clamp(&self.min, &self.max)
+    }
+
+    pub fn distance_to_point(&self, p: &Pt3) -> f32 {
+        Vec3::from(self.closest_point(p) - p).norm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use crate::{assert_approx_eq, transform4::T4Mul};
+
+    use super::*;
+
+    #[test]
+    fn builds_from_points() {
+        let b = Aabb::from_points(&[
+            Pt3::new(1., -2., 0.),
+            Pt3::new(-1., 3., 2.),
+            Pt3::new(0., 0., -4.),
+        ])
+        .unwrap();
+        assert_approx_eq!(b.min, Pt3::new(-1., -2., -4.));
+        assert_approx_eq!(b.max, Pt3::new(1., 3., 2.));
+        assert_approx_eq!(b.center(), Pt3::new(0., 0.5, -1.));
+        assert!(Aabb::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn merges_and_contains() {
+        let a = Aabb::new(Pt3::new(0., 0., 0.), Pt3::new(1., 1., 1.));
+        let b = Aabb::new(Pt3::new(2., -1., 0.), Pt3::new(3., 0., 1.));
+        let u = a.union(&b);
+        assert!(u.contains(&a) && u.contains(&b));
+        assert!(!a.contains(&u));
+        assert!(!a.overlaps(&b));
+        assert!(u.contains_point(&Pt3::new(2.5, 0.5, 0.5)));
+        assert!(a
+            .include(&Pt3::new(-1., 0., 0.))
+            .contains_point(&Pt3::new(-0.5, 0., 0.)));
+        assert!(a.expand(0.5).contains_point(&Pt3::new(1.4, 1.4, -0.4)));
+        assert!(a.expand(-1.).is_empty());
+    }
+
+    #[test]
+    fn transforms_conservatively() {
+        let a = Aabb::new(Pt3::new(-1., -1., -1.), Pt3::new(1., 1., 1.));
+        let mut m = Mat4::from(Mat3::from_angle_z(FRAC_PI_4));
+        m.set_translation(Vec3::new(5., 0., 0.));
+        let b = a.transform(&m);
+        let r = 2_f32.sqrt();
+        assert_approx_eq!(b.min, Pt3::new(5. - r, -r, -1.));
+        assert_approx_eq!(b.max, Pt3::new(5. + r, r, 1.));
+        for p in a.corners() {
+            assert!(b.expand(1e-5).contains_point(&T4Mul::mul(&m, &p)));
+        }
+
+        let empty = Aabb::EMPTY.transform(&m);
+        assert!(empty.is_empty());
+        assert_eq!(empty.min.x, f32::INFINITY);
+        assert_eq!(empty.max.x, f32::NEG_INFINITY);
+        assert!(empty.union(&b).contains(&b));
+    }
+
+    #[test]
+    fn finds_closest_point() {
+        let a = Aabb::new(Pt3::new(0., 0., 0.), Pt3::new(1., 1., 1.));
+        assert_approx_eq!(
+            a.closest_point(&Pt3::new(2., 0.5, -1.)),
+            Pt3::new(1., 0.5, 0.)
+        );
+        assert_eq!(a.distance_to_point(&Pt3::new(0.5, 0.5, 0.5)), 0.);
+        assert_eq!(a.surface_area(), 6.);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{
+    impl_op,
+    mat3::Mat3,
+    mat4::Mat4,
+    plane::Plane,
+    pt3::Pt3,
+    structure::{SquareMat, VecSpace},
+    transform4::T4Mul,
+    vec3::Vec3,
+    vec4::Vec4,
+};
+use std::{error::Error, fmt, ops};
+
+/// Affine map of 3D space, stored as the top three rows of its `Mat4`
+/// whose bottom row is always `[0, 0, 0, 1]`. Rows hold the linear part in
+/// `xyz` and the translation in `w`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct Affine3 {
+    pub a: Vec4,
+    pub b: Vec4,
+    pub c: Vec4,
+}
+
+/// Error converting a `Mat4` whose bottom row is not `[0, 0, 0, 1]` to an
+/// `Affine3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotAffine;
+
+impl fmt::Display for NotAffine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("matrix is projective, its bottom row is not [0, 0, 0, 1]")
+    }
+}
+
+impl Error for NotAffine {}
+
+impl Affine3 {
+    pub const IDENTITY: Self = Self {
+        a: Vec4::X,
+        b: Vec4::Y,
+        c: Vec4::Z,
+    };
+
+    pub fn new(linear: &Mat3, translation: Vec3) -> Self {
+        Self {
+            a: linear.a.extend(translation.x),
+            b: linear.b.extend(translation.y),
+            c: linear.c.extend(translation.z),
+        }
+    }
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self::new(&Mat3::IDENTITY, translation)
+    }
+
+    pub fn from_linear(linear: &Mat3) -> Self {
+        Self::new(linear, Vec3::ZERO)
+    }
+
+    pub fn linear(&self) -> Mat3 {
+        Mat3 {
+            a: self.a.xyz(),
+            b: self.b.xyz(),
+            c: self.c.xyz(),
+        }
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        Vec3::new(self.a.w, self.b.w, self.c.w)
+    }
+
+    pub fn set_translation(&mut self, t: Vec3) {
+        self.a.w = t.x;
+        self.b.w = t.y;
+        self.c.w = t.z;
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.linear().determinant()
+    }
+
+    /// `None` when the linear part is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let linear = self.linear().invert()?;
+        Some(Self::new(&linear, -(linear * self.translation())))
+    }
+
+    pub fn transform_point(&self, p: &Pt3) -> Pt3 {
+        (self.linear() * Vec3::from(p) + self.translation()).into()
+    }
+
+    /// Maps a direction, ignoring the translation.
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        self.linear() * *v
+    }
+
+    /// Maps a surface normal with the inverse transpose of the linear part,
+    /// returning a unit vector.
+    pub fn transform_normal(&self, n: &Vec3) -> Vec3 {
+        // The cofactor matrix is the inverse transpose scaled by the
+        // determinant, whose sign keeps mirrored normals facing out
+        let linear = self.linear();
+        (linear.cofactor() * *n * linear.determinant().signum()).normalize()
+    }
+
+    /// Image of `f`, `None` when the map is singular. Its normal follows
+    /// `transform_normal` and the distance is scaled along with it.
+    pub fn transform_plane(&self, f: &Plane) -> Option<Plane> {
+        Some(self.inverse()?.pull_back_plane(f))
+    }
+
+    /// Plane whose image is `f`, a plane transform by the inverse map that
+    /// needs no inversion.
+    pub fn pull_back_plane(&self, f: &Plane) -> Plane {
+        T4Mul::mul(&Mat4::from(self), f)
+    }
+}
+
+impl TryFrom<&Mat4> for Affine3 {
+    type Error = NotAffine;
+
+    fn try_from(m: &Mat4) -> Result<Self, NotAffine> {
+        if m.is_affine() {
+            Ok(Self {
+                a: m.a,
+                b: m.b,
+                c: m.c,
+            })
+        } else {
+            Err(NotAffine)
+        }
+    }
+}
+
+impl TryFrom<Mat4> for Affine3 {
+    type Error = NotAffine;
+
+    fn try_from(m: Mat4) -> Result<Self, NotAffine> {
+        Self::try_from(&m)
+    }
+}
+
+impl_op!(Affine3 : Affine3, ops::Mul { fn mul |lhs: &Affine3, rhs: &Affine3| {
+    let linear = lhs.linear();
+    Affine3::new(
+        &(linear * rhs.linear()),
+        linear * rhs.translation() + lhs.translation(),
+    )
+}});
+
+impl_op!(Affine3 : Pt3 => Pt3, ops::Mul { fn mul |m: &Affine3, p: &Pt3| {
+    m.transform_point(p)
+}});
+
+impl_op!(Affine3 : Vec3 => Vec3, ops::Mul { fn mul |m: &Affine3, v: &Vec3| {
+    m.transform_vector(v)
+}});
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::{assert_approx_eq, structure::EuclideanSpace, transform4::Transform4};
+
+    use super::*;
+
+    fn affine() -> Affine3 {
+        Affine3::new(
+            &(Mat3::from_angle_z(FRAC_PI_2) * Mat3::from_diagonal(Vec3::new(2., 1., 1.))),
+            Vec3::new(1., 2., 3.),
+        )
+    }
+
+    #[test]
+    fn transforms_points_and_vectors() {
+        let m = affine();
+        assert_approx_eq!(m * Pt3::new(1., 0., 0.), Pt3::new(1., 4., 3.));
+        assert_eq!(m * Vec3::X, Vec3::new(0., 2., 0.));
+        assert_approx_eq!(
+            Affine3::IDENTITY * Pt3::new(4., 5., 6.),
+            Pt3::new(4., 5., 6.)
+        );
+    }
+
+    #[test]
+    fn composes_and_inverts() {
+        let m = affine();
+        let n = Affine3::from_translation(Vec3::new(-1., 0., 5.))
+            * Affine3::from_linear(&Mat3::from_angle_x(0.3));
+        let p = Pt3::new(0.5, -2., 7.);
+        assert_approx_eq!((m * n) * p, m * (n * p));
+
+        let inv = m.inverse().unwrap();
+        assert_eq!(Mat4::from(m * inv), Mat4::IDENTITY);
+        assert_approx_eq!(inv * (m * p), p);
+        assert!(
+            Affine3::from_linear(&Mat3::from_diagonal(Vec3::new(1., 0., 1.)))
+                .inverse()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn transforms_normals_and_planes() {
+        let m = affine();
+        // The plane x = 1 maps to y = 4 after scaling by 2 and turning
+        let f = Plane::new(1., 0., 0., -1.);
+        let g = m.transform_plane(&f).unwrap();
+        assert!(g.dot(&(m * Pt3::new(1., 5., -2.))).abs() < 1e-5);
+        assert_eq!(g.normal().normalize(), m.transform_normal(&f.normal()));
+        assert_eq!(m.transform_normal(&Vec3::X), Vec3::Y);
+        assert_eq!(Vec4::new(g.x, g.y, g.z, g.d), Vec4::new(0., 0.5, 0., -2.));
+
+        let back = m.pull_back_plane(&g);
+        assert_eq!(
+            Vec4::new(back.x, back.y, back.z, back.d),
+            Vec4::new(f.x, f.y, f.z, f.d)
+        );
+
+        // Mirrored normals still face out
+        let mirror = Affine3::from_linear(&Mat3::from_diagonal(Vec3::new(-1., 1., 1.)));
+        assert_eq!(mirror.transform_normal(&Vec3::X), -Vec3::X);
+    }
+
+    #[test]
+    fn converts_to_and_from_mat4() {
+        let m = affine();
+        let h = Mat4::from(m);
+        assert!(h.is_affine());
+        assert!(!Mat4::perspective_rh(1., 1., 0.1, 10.).is_affine());
+        assert_eq!(h.get_translation(), m.translation());
+        assert_eq!(Mat3::from(h), m.linear());
+        let back = Affine3::try_from(h).unwrap();
+        assert_eq!(Mat4::from(back), h);
+        assert_eq!(
+            Affine3::try_from(Mat4::perspective_rh(1., 1., 0.1, 10.)),
+            Err(NotAffine)
+        );
+
+        // A bottom row within the `==` tolerance of affine is still projective
+        let mut nearly = h;
+        nearly.d = Vec4::new(0., 0., -0.0009, 1.0009);
+        assert!(!nearly.is_affine());
+        assert_eq!(Affine3::try_from(nearly), Err(NotAffine));
+        assert!(nearly.decompose().is_none());
+        assert_eq!(
+            Mat4::from(m.inverse().unwrap()),
+            Transform4::invert(&h).unwrap()
+        );
+    }
+}
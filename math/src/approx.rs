@@ -0,0 +1,280 @@
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    affine3::Affine3,
+    dmat3::DMat3,
+    dmat4::DMat4,
+    dplane::DPlane,
+    dpt3::DPt3,
+    dquat::DQuat,
+    dual_quat::DualQuat,
+    dvec3::DVec3,
+    dvec4::DVec4,
+    grassmann::{Bivector3, PluckerLine, Trivector},
+    mat2::Mat2,
+    mat3::Mat3,
+    mat4::Mat4,
+    plane::Plane,
+    pt2::Pt2,
+    pt3::Pt3,
+    quat::Quat,
+    scalar::Scalar,
+    transform::Transform,
+    vec2::Vec2,
+    vec3::Vec3,
+    vec4::Vec4,
+};
+
+/// Component-wise comparison with an explicit tolerance. Unlike the
+/// `PartialEq` impls, which use the fixed `PRECISION` margin, the caller
+/// picks the mode and the margin.
+pub trait ApproxEq {
+    type Scalar: Scalar;
+    type Components: AsRef<[Self::Scalar]>;
+
+    /// Components in a fixed order, row by row for matrices.
+    fn components(&self) -> Self::Components;
+
+    /// Every component is within `epsilon` of the other one.
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Scalar) -> bool {
+        zip_all(self, other, |a, b| a == b || (a - b).abs() <= epsilon)
+    }
+
+    /// Every component is within `epsilon` of the other one, or within
+    /// `max_relative` times the larger of their magnitudes.
+    fn relative_eq(&self, other: &Self, epsilon: Self::Scalar, max_relative: Self::Scalar) -> bool {
+        zip_all(self, other, |a, b| {
+            if a == b {
+                return true;
+            }
+            if !a.is_finite() || !b.is_finite() {
+                return false;
+            }
+            let diff = (a - b).abs();
+            diff <= epsilon || diff <= a.abs().max(b.abs()) * max_relative
+        })
+    }
+
+    /// Every component is within `epsilon` of the other one, or at most
+    /// `max_ulps` representable values away from it.
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Scalar, max_ulps: u32) -> bool {
+        zip_all(self, other, |a, b| {
+            a == b || (a - b).abs() <= epsilon || a.ulps(b) <= max_ulps as u64
+        })
+    }
+}
+
+fn zip_all<T: ApproxEq + ?Sized>(
+    lhs: &T,
+    rhs: &T,
+    eq: impl Fn(T::Scalar, T::Scalar) -> bool,
+) -> bool {
+    let (lhs, rhs) = (lhs.components(), rhs.components());
+    lhs.as_ref()
+        .iter()
+        .zip(rhs.as_ref())
+        .all(|(a, b)| eq(*a, *b))
+}
+
+/// Bitwise equality, which unlike `PartialEq` is transitive and so can back
+/// `Eq` and `Hash`, e.g. to key a map by vertex positions. `0.` and `-0.`
+/// differ and a NaN equals itself.
+#[derive(Clone, Copy, Debug)]
+pub struct Exact<T>(pub T);
+
+impl<T: ApproxEq> PartialEq for Exact<T> {
+    fn eq(&self, other: &Self) -> bool {
+        zip_all(&self.0, &other.0, |a, b| a.bits() == b.bits())
+    }
+}
+
+impl<T: ApproxEq> Eq for Exact<T> {}
+
+impl<T: ApproxEq> Hash for Exact<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.0.components().as_ref() {
+            state.write_u64(c.bits());
+        }
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_abs<T: ApproxEq + Debug>(
+    left: &T,
+    right: &T,
+    epsilon: Option<T::Scalar>,
+    max_ulps: Option<u32>,
+) {
+    let ok = match max_ulps {
+        // Pure ulps comparison unless a floor is given
+        Some(ulps) => left.ulps_eq(right, epsilon.unwrap_or(T::Scalar::ZERO), ulps),
+        None => left.abs_diff_eq(right, epsilon.unwrap_or(T::Scalar::DEFAULT_EPSILON)),
+    };
+    if !ok {
+        let mode = match (epsilon, max_ulps) {
+            (Some(e), Some(u)) => format!("epsilon = {e:?}, ulps = {u}"),
+            (None, Some(u)) => format!("ulps = {u}"),
+            (e, None) => format!("epsilon = {:?}", e.unwrap_or(T::Scalar::DEFAULT_EPSILON)),
+        };
+        failed(left, right, &mode);
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_rel<T: ApproxEq + Debug>(left: &T, right: &T, max_relative: Option<T::Scalar>) {
+    let max_relative = max_relative.unwrap_or(T::Scalar::DEFAULT_MAX_RELATIVE);
+    // Without an absolute floor a component only matches an exact zero
+    if !left.relative_eq(right, T::Scalar::ZERO, max_relative) {
+        failed(left, right, &format!("max_relative = {max_relative:?}"));
+    }
+}
+
+#[track_caller]
+fn failed<T: ApproxEq + Debug>(left: &T, right: &T, mode: &str) -> ! {
+    let (l, r) = (left.components(), right.components());
+    let diffs: Vec<String> = l
+        .as_ref()
+        .iter()
+        .zip(r.as_ref())
+        .enumerate()
+        .filter(|(_, (a, b))| a.bits() != b.bits())
+        .map(|(i, (a, b))| format!("    [{i}] {a:?} vs {b:?}, diff {:?}", (*a - *b).abs()))
+        .collect();
+    panic!(
+        "assertion `left ≈ right` failed ({mode})\n  left: {left:?}\n right: {right:?}\n  diff:\n{}",
+        diffs.join("\n")
+    );
+}
+
+fn flatten<S: Copy, const R: usize, const C: usize, const N: usize>(rows: [[S; C]; R]) -> [S; N] {
+    rows.as_flattened()
+        .try_into()
+        .expect("component count of a matrix")
+}
+
+macro_rules! impl_approx {
+    ($A:ty, $S:ty, $n:literal, |$v:ident| $components:expr) => {
+        impl ApproxEq for $A {
+            type Scalar = $S;
+            type Components = [$S; $n];
+
+            fn components(&self) -> [$S; $n] {
+                let $v = self;
+                $components
+            }
+        }
+    };
+}
+
+impl_approx!(Vec2, f32, 2, |v| v.into());
+impl_approx!(Vec3, f32, 3, |v| v.into());
+impl_approx!(Vec4, f32, 4, |v| v.into());
+impl_approx!(Pt2, f32, 2, |p| p.into());
+impl_approx!(Pt3, f32, 3, |p| p.into());
+impl_approx!(Quat, f32, 4, |q| q.into());
+impl_approx!(Plane, f32, 4, |f| f.into());
+impl_approx!(Mat2, f32, 4, |m| flatten(<[[f32; 2]; 2]>::from(m)));
+impl_approx!(Mat3, f32, 9, |m| flatten(<[[f32; 3]; 3]>::from(m)));
+impl_approx!(Mat4, f32, 16, |m| flatten(<[[f32; 4]; 4]>::from(m)));
+impl_approx!(Affine3, f32, 12, |m| {
+    flatten([m.a.into(), m.b.into(), m.c.into()])
+});
+impl_approx!(DualQuat, f32, 8, |q| {
+    flatten([q.real.into(), q.dual.into()])
+});
+impl_approx!(Transform, f32, 10, |t| {
+    let [tx, ty, tz]: [f32; 3] = t.translation.into();
+    let [rx, ry, rz, rw]: [f32; 4] = t.rotation.into();
+    let [sx, sy, sz]: [f32; 3] = t.scale.into();
+    [tx, ty, tz, rx, ry, rz, rw, sx, sy, sz]
+});
+impl_approx!(Bivector3, f32, 3, |b| [b.yz, b.zx, b.xy]);
+impl_approx!(Trivector, f32, 1, |t| [t.xyz]);
+impl_approx!(PluckerLine, f32, 6, |l| {
+    flatten([l.dir.into(), l.moment.into()])
+});
+
+impl_approx!(DVec3, f64, 3, |v| v.into());
+impl_approx!(DVec4, f64, 4, |v| v.into());
+impl_approx!(DPt3, f64, 3, |p| p.into());
+impl_approx!(DQuat, f64, 4, |q| q.into());
+impl_approx!(DPlane, f64, 4, |f| [f.x, f.y, f.z, f.d]);
+impl_approx!(DMat3, f64, 9, |m| flatten(<[[f64; 3]; 3]>::from(m)));
+impl_approx!(DMat4, f64, 16, |m| flatten(<[[f64; 4]; 4]>::from(m)));
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{assert_approx_eq, assert_rel_eq, structure::SquareMat};
+
+    use super::*;
+
+    #[test]
+    fn compares_in_each_mode() {
+        let a = Vec3::new(1., 1000., 0.);
+        let b = Vec3::new(1.001, 1000.5, 0.);
+        assert!(a.abs_diff_eq(&b, 0.6));
+        assert!(!a.abs_diff_eq(&b, 0.1));
+        assert!(a.relative_eq(&b, 0., 1e-3));
+        assert!(!a.relative_eq(&b, 0., 1e-4));
+
+        let next = f32::from_bits(1f32.to_bits() + 2);
+        let p = Pt3::new(1., 2., 3.);
+        let q = Pt3::new(next, 2., 3.);
+        assert!(p.ulps_eq(&q, 0., 2));
+        assert!(!p.ulps_eq(&q, 0., 1));
+        assert!(!Vec2::new(f32::NAN, 0.).ulps_eq(&Vec2::new(f32::NAN, 0.), 0., 4));
+
+        let f = Plane::new(0., 1., 0., -2.);
+        assert!(f.abs_diff_eq(&Plane::new(0., 1., 1e-4, -2.), 1e-3));
+        assert!(Mat4::IDENTITY.relative_eq(&Mat4::IDENTITY, 0., 0.));
+        assert!(DVec3::new(1., 2., 3.).abs_diff_eq(&DVec3::new(1., 2., 3. + 1e-10), 1e-9));
+    }
+
+    #[test]
+    fn exact_form_is_transitive_and_hashes() {
+        let a = Vec3::new(0., 0., 0.);
+        let b = Vec3::new(0., 0., 8e-4);
+        let c = Vec3::new(0., 0., 1.6e-3);
+        // The tolerant `==` chains a == b == c but not a == c
+        assert!(a == b && b == c && a != c);
+        assert!(Exact(a) != Exact(b));
+        assert!(Exact(Vec3::new(0., 0., -0.)) != Exact(a));
+        assert!(Exact(Vec3::new(f32::NAN, 0., 0.)) == Exact(Vec3::new(f32::NAN, 0., 0.)));
+
+        let set: HashSet<_> = [a, b, a, c].into_iter().map(Exact).collect();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&Exact(Vec3::new(0., 0., 8e-4))));
+    }
+
+    #[test]
+    fn assertion_macros_pass() {
+        assert_approx_eq!(Pt3::new(1., 2., 3.), Pt3::new(1., 2., 3.0005));
+        assert_approx_eq!(Mat3::IDENTITY, Mat3::IDENTITY * 1.00001, epsilon = 1e-4);
+        assert_approx_eq!(Quat::IDENTITY, Quat::IDENTITY, ulps = 0);
+        assert_rel_eq!(Vec3::new(1e6, 2., 0.), Vec3::new(1e6 + 1., 2., 0.));
+        assert_rel_eq!(
+            Plane::new(0., 2., 0., 100.),
+            Plane::new(0., 2.01, 0., 100.5),
+            max_relative = 1e-2,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "[1] 2.0 vs 2.5, diff 0.5")]
+    fn assertion_reports_component_diffs() {
+        assert_approx_eq!(Vec3::new(1., 2., 3.), Vec3::new(1., 2.5, 3.));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_relative")]
+    fn relative_assertion_fails() {
+        assert_rel_eq!(Vec2::new(1., 0.), Vec2::new(1., 1e-9));
+    }
+}
@@ -0,0 +1,674 @@
+use crate::{
+    aabb::Aabb,
+    capsule::Capsule,
+    geometry::{Linear, Ray},
+    obb::Obb,
+    pt3::Pt3,
+    raycast::{slabs, RayCast, RayHit},
+    sphere::Sphere,
+    structure::VecSpace,
+    triangle::Triangle,
+    vec3::Vec3,
+};
+
+/// Shapes with an axis-aligned bounding box.
+pub trait Bounded {
+    fn bounds(&self) -> Aabb;
+}
+
+/// Shapes that can find their point closest to a query point.
+pub trait ClosestPoint {
+    fn closest_point(&self, p: &Pt3) -> Pt3;
+}
+
+impl Bounded for Pt3 {
+    fn bounds(&self) -> Aabb {
+        Aabb::new(*self, *self)
+    }
+}
+
+impl Bounded for Aabb {
+    fn bounds(&self) -> Aabb {
+        *self
+    }
+}
+
+impl Bounded for Obb {
+    fn bounds(&self) -> Aabb {
+        self.to_aabb()
+    }
+}
+
+impl Bounded for Sphere {
+    fn bounds(&self) -> Aabb {
+        let r = self.radius;
+        Aabb::from_center(self.center, Vec3::new(r, r, r))
+    }
+}
+
+impl Bounded for Capsule {
+    fn bounds(&self) -> Aabb {
+        Capsule::bounds(self)
+    }
+}
+
+impl Bounded for Triangle {
+    fn bounds(&self) -> Aabb {
+        Triangle::bounds(self)
+    }
+}
+
+impl ClosestPoint for Pt3 {
+    fn closest_point(&self, _: &Pt3) -> Pt3 {
+        *self
+    }
+}
+
+impl ClosestPoint for Aabb {
+    fn closest_point(&self, p: &Pt3) -> Pt3 {
+        Aabb::closest_point(self, p)
+    }
+}
+
+impl ClosestPoint for Obb {
+    fn closest_point(&self, p: &Pt3) -> Pt3 {
+        Obb::closest_point(self, p)
+    }
+}
+
+impl ClosestPoint for Sphere {
+    fn closest_point(&self, p: &Pt3) -> Pt3 {
+        let d = Vec3::from(*p - self.center);
+        let dist = d.norm();
+        if dist <= self.radius {
+            *p
+        } else {
+            self.center + d * (self.radius / dist)
+        }
+    }
+}
+
+impl ClosestPoint for Capsule {
+    fn closest_point(&self, p: &Pt3) -> Pt3 {
+        let axis = self.segment().closest_point(p);
+        Sphere::new(axis, self.radius).closest_point(p)
+    }
+}
+
+impl ClosestPoint for Triangle {
+    fn closest_point(&self, p: &Pt3) -> Pt3 {
+        Triangle::closest_point(self, p)
+    }
+}
+
+const BINS: usize = 16;
+const MAX_LEAF_SIZE: usize = 8;
+
+/// Cost of visiting a node relative to testing a primitive.
+const TRAVERSAL_COST: f32 = 1.;
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bounds: Aabb,
+    /// Index of the first child for inner nodes, whose children are
+    /// adjacent, or of the first entry of `order` for leaves.
+    first: usize,
+    /// Number of primitives, zero for inner nodes.
+    count: usize,
+}
+
+impl Node {
+    fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+/// Bounding volume hierarchy over primitives, built with the surface area
+/// heuristic. Queries report primitives by their index in the slice the
+/// hierarchy was built from.
+///
+/// After moving primitives through `primitives_mut`, `refit` updates the
+/// bounds while keeping the tree; rebuild once the primitives have moved
+/// far enough for the tree to degrade.
+#[derive(Clone, Debug)]
+pub struct Bvh<P> {
+    primitives: Vec<P>,
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl<P: Bounded> Bvh<P> {
+    pub fn new(primitives: Vec<P>) -> Self {
+        let bounds: Vec<Aabb> = primitives.iter().map(Bounded::bounds).collect();
+        let centers: Vec<Pt3> = bounds.iter().map(Aabb::center).collect();
+        let mut order: Vec<usize> = (0..primitives.len()).collect();
+        let mut nodes = Vec::with_capacity(2 * primitives.len());
+        if !primitives.is_empty() {
+            nodes.push(Node {
+                bounds: Aabb::EMPTY,
+                first: 0,
+                count: 0,
+            });
+            build(&mut nodes, 0, &mut order, 0, &bounds, &centers);
+        }
+        Self {
+            primitives,
+            order,
+            nodes,
+        }
+    }
+
+    /// Recomputes the bounds of every node from the primitives.
+    pub fn refit(&mut self) {
+        // Children come after their parents
+        for i in (0..self.nodes.len()).rev() {
+            let node = self.nodes[i];
+            self.nodes[i].bounds = if node.is_leaf() {
+                self.order[node.first..node.first + node.count]
+                    .iter()
+                    .fold(Aabb::EMPTY, |b, &k| b.union(&self.primitives[k].bounds()))
+            } else {
+                self.nodes[node.first]
+                    .bounds
+                    .union(&self.nodes[node.first + 1].bounds)
+            };
+        }
+    }
+
+    /// Primitives whose bounds overlap `b`.
+    pub fn overlapping(&self, b: &Aabb) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.traverse(
+            |node| node.bounds.overlaps(b),
+            |k| {
+                if self.primitives[k].bounds().overlaps(b) {
+                    found.push(k);
+                }
+                false
+            },
+        );
+        found
+    }
+}
+
+impl Bvh<Triangle> {
+    /// Hierarchy over a triangle soup, every three positions making up a
+    /// triangle.
+    pub fn from_soup<V: Copy + Into<Pt3>>(positions: &[V]) -> Self {
+        Self::new(
+            positions
+                .chunks_exact(3)
+                .map(|t| Triangle::new(t[0].into(), t[1].into(), t[2].into()))
+                .collect(),
+        )
+    }
+
+    /// Hierarchy over an indexed triangle mesh.
+    pub fn from_indexed<V: Copy + Into<Pt3>>(positions: &[V], indices: &[[u32; 3]]) -> Self {
+        Self::new(
+            indices
+                .iter()
+                .map(|t| {
+                    let [a, b, c] = t.map(|i| positions[i as usize].into());
+                    Triangle::new(a, b, c)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<P> Bvh<P> {
+    pub fn primitives(&self) -> &[P] {
+        &self.primitives
+    }
+
+    /// Call `refit` once done moving primitives.
+    pub fn primitives_mut(&mut self) -> &mut [P] {
+        &mut self.primitives
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Bounds of all primitives, empty for an empty hierarchy.
+    pub fn bounds(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::EMPTY, |n| n.bounds)
+    }
+
+    // Depth first walk into the nodes accepted by `enter`, calling `visit`
+    // on their primitives until it returns true
+    fn traverse(&self, enter: impl Fn(&Node) -> bool, mut visit: impl FnMut(usize) -> bool) {
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !enter(node) {
+                continue;
+            }
+            if node.is_leaf() {
+                for &k in &self.order[node.first..node.first + node.count] {
+                    if visit(k) {
+                        return;
+                    }
+                }
+            } else {
+                stack.push(node.first + 1);
+                stack.push(node.first);
+            }
+        }
+    }
+}
+
+impl<P: RayCast> Bvh<P> {
+    /// Closest hit with `0 <= t <= max_t` and the primitive hit.
+    pub fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<(usize, RayHit)> {
+        let origin = Vec3::from(ray.origin);
+        let entry = |node: &Node, max_t: f32| {
+            slabs(
+                &origin,
+                &ray.dir,
+                &node.bounds.min.into(),
+                &node.bounds.max.into(),
+                max_t,
+            )
+            .map(|(t, _)| t)
+        };
+
+        let mut best: Option<(usize, RayHit)> = None;
+        let mut max_t = max_t;
+        let mut stack = Vec::with_capacity(64);
+        if let Some(root) = self.nodes.first() {
+            if let Some(t) = entry(root, max_t) {
+                stack.push((0, t));
+            }
+        }
+        while let Some((i, t)) = stack.pop() {
+            // Warning: the entry parameter was computed against an older
+            // and possibly larger max_t
+            if t > max_t {
+                continue;
+            }
+            let node = &self.nodes[i];
+            if node.is_leaf() {
+                for &k in &self.order[node.first..node.first + node.count] {
+                    if let Some(hit) = self.primitives[k].raycast_within(ray, max_t) {
+                        max_t = hit.t;
+                        best = Some((k, hit));
+                    }
+                }
+                continue;
+            }
+
+            // Visit the nearer child first
+            let children =
+                [node.first, node.first + 1].map(|c| entry(&self.nodes[c], max_t).map(|t| (c, t)));
+            match children {
+                [Some(a), Some(b)] if a.1 <= b.1 => stack.extend([b, a]),
+                [Some(a), Some(b)] => stack.extend([a, b]),
+                [Some(a), None] | [None, Some(a)] => stack.push(a),
+                [None, None] => {}
+            }
+        }
+        best
+    }
+
+    pub fn raycast(&self, ray: &Ray) -> Option<(usize, RayHit)> {
+        self.raycast_within(ray, f32::INFINITY)
+    }
+
+    /// Some hit with `0 <= t <= max_t`, not necessarily the closest one.
+    /// Enough for shadow rays and line of sight checks.
+    pub fn raycast_any(&self, ray: &Ray, max_t: f32) -> Option<(usize, RayHit)> {
+        let origin = Vec3::from(ray.origin);
+        let mut found = None;
+        self.traverse(
+            |node| {
+                slabs(
+                    &origin,
+                    &ray.dir,
+                    &node.bounds.min.into(),
+                    &node.bounds.max.into(),
+                    max_t,
+                )
+                .is_some()
+            },
+            |k| {
+                found = self.primitives[k]
+                    .raycast_within(ray, max_t)
+                    .map(|hit| (k, hit));
+                found.is_some()
+            },
+        );
+        found
+    }
+}
+
+impl<P: ClosestPoint> Bvh<P> {
+    /// Point of the primitives closest to `p` and the primitive it lies on.
+    pub fn nearest(&self, p: &Pt3) -> Option<(usize, Pt3)> {
+        let dist2 = |b: &Aabb| Vec3::from(b.closest_point(p) - p).norm2();
+
+        let mut best: Option<(usize, Pt3)> = None;
+        let mut best_dist2 = f32::INFINITY;
+        let mut stack = Vec::with_capacity(64);
+        if let Some(root) = self.nodes.first() {
+            stack.push((0, dist2(&root.bounds)));
+        }
+        while let Some((i, d)) = stack.pop() {
+            if d >= best_dist2 {
+                continue;
+            }
+            let node = &self.nodes[i];
+            if node.is_leaf() {
+                for &k in &self.order[node.first..node.first + node.count] {
+                    let q = self.primitives[k].closest_point(p);
+                    let d = Vec3::from(q - p).norm2();
+                    if d < best_dist2 {
+                        best_dist2 = d;
+                        best = Some((k, q));
+                    }
+                }
+                continue;
+            }
+
+            let [a, b] = [node.first, node.first + 1].map(|c| (c, dist2(&self.nodes[c].bounds)));
+            if a.1 <= b.1 {
+                stack.extend([b, a]);
+            } else {
+                stack.extend([a, b]);
+            }
+        }
+        best
+    }
+}
+
+fn build(
+    nodes: &mut Vec<Node>,
+    index: usize,
+    order: &mut [usize],
+    offset: usize,
+    bounds: &[Aabb],
+    centers: &[Pt3],
+) {
+    let count = order.len();
+    let node_bounds = order.iter().fold(Aabb::EMPTY, |b, &k| b.union(&bounds[k]));
+    nodes[index] = Node {
+        bounds: node_bounds,
+        first: offset,
+        count,
+    };
+    if count == 1 {
+        return;
+    }
+
+    let mid = match split(order, node_bounds.surface_area(), bounds, centers) {
+        Some(mid) => mid,
+        None if count <= MAX_LEAF_SIZE => return,
+        // Coincident centers, split anywhere
+        None => count / 2,
+    };
+
+    let first = nodes.len();
+    nodes[index].first = first;
+    nodes[index].count = 0;
+    nodes.extend([nodes[index]; 2]);
+    let (left, right) = order.split_at_mut(mid);
+    build(nodes, first, left, offset, bounds, centers);
+    build(nodes, first + 1, right, offset + mid, bounds, centers);
+}
+
+/// Partitions `order` along the binned split of least surface area cost,
+/// see Wald, "On Fast Construction of SAH-based Bounding Volume
+/// Hierarchies". Returns the size of the first part, `None` when keeping a
+/// leaf is cheaper or no split exists.
+fn split(order: &mut [usize], area: f32, bounds: &[Aabb], centers: &[Pt3]) -> Option<usize> {
+    let count = order.len();
+    let extent = order
+        .iter()
+        .fold(Aabb::EMPTY, |b, &k| b.include(&centers[k]));
+
+    let bin = |k: usize, axis: usize| {
+        let (lo, hi) = (extent.min[axis], extent.max[axis]);
+        let b = ((centers[k][axis] - lo) / (hi - lo) * BINS as f32) as usize;
+        b.min(BINS - 1)
+    };
+
+    // Cheapest split on any axis as (cost, axis, bins on the left)
+    let mut best: Option<(f32, usize, usize)> = None;
+    for axis in 0..3 {
+        if extent.max[axis] <= extent.min[axis] {
+            continue;
+        }
+        let mut bin_bounds = [Aabb::EMPTY; BINS];
+        let mut bin_counts = [0usize; BINS];
+        for &k in order.iter() {
+            let b = bin(k, axis);
+            bin_bounds[b] = bin_bounds[b].union(&bounds[k]);
+            bin_counts[b] += 1;
+        }
+
+        // Areas and counts to the right of each split, swept from the end
+        let mut right_area = [0.; BINS];
+        let mut right_count = [0; BINS];
+        let (mut acc, mut n) = (Aabb::EMPTY, 0);
+        for b in (1..BINS).rev() {
+            acc = acc.union(&bin_bounds[b]);
+            n += bin_counts[b];
+            right_area[b] = acc.surface_area();
+            right_count[b] = n;
+        }
+
+        let (mut acc, mut n) = (Aabb::EMPTY, 0);
+        for b in 1..BINS {
+            acc = acc.union(&bin_bounds[b - 1]);
+            n += bin_counts[b - 1];
+            if n == 0 || right_count[b] == 0 {
+                continue;
+            }
+            let cost = TRAVERSAL_COST
+                + (acc.surface_area() * n as f32 + right_area[b] * right_count[b] as f32) / area;
+            if best.is_none_or(|(c, _, _)| cost < c) {
+                best = Some((cost, axis, b));
+            }
+        }
+    }
+
+    let (cost, axis, split_bin) = best?;
+    if cost >= count as f32 && count <= MAX_LEAF_SIZE {
+        return None;
+    }
+
+    let mut mid = 0;
+    for i in 0..count {
+        if bin(order[i], axis) < split_bin {
+            order.swap(i, mid);
+            mid += 1;
+        }
+    }
+    Some(mid)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_approx_eq;
+
+    use super::*;
+
+    // Deterministic values in [-1, 1)
+    fn random(seed: &mut u32) -> f32 {
+        *seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        (*seed >> 8) as f32 / (1 << 23) as f32 - 1.
+    }
+
+    fn random_pt(seed: &mut u32, scale: f32) -> Pt3 {
+        Pt3::new(random(seed), random(seed), random(seed)) * scale
+    }
+
+    fn triangles(n: usize) -> Vec<Triangle> {
+        let mut seed = 7;
+        (0..n)
+            .map(|_| {
+                let c = random_pt(&mut seed, 10.);
+                Triangle::new(
+                    c + random_pt(&mut seed, 1.),
+                    c + random_pt(&mut seed, 1.),
+                    c + random_pt(&mut seed, 1.),
+                )
+            })
+            .collect()
+    }
+
+    fn rays(n: usize) -> Vec<Ray> {
+        let mut seed = 11;
+        (0..n)
+            .map(|_| {
+                let origin = random_pt(&mut seed, 15.);
+                let target = random_pt(&mut seed, 5.);
+                Ray::new(origin, (target - origin).into())
+            })
+            .collect()
+    }
+
+    fn brute_raycast<P: RayCast>(ps: &[P], ray: &Ray) -> Option<(usize, RayHit)> {
+        ps.iter()
+            .enumerate()
+            .filter_map(|(k, p)| p.raycast(ray).map(|hit| (k, hit)))
+            .min_by(|a, b| a.1.t.total_cmp(&b.1.t))
+    }
+
+    fn check_bounds<P: Bounded>(bvh: &Bvh<P>) {
+        for node in &bvh.nodes {
+            if node.is_leaf() {
+                for &k in &bvh.order[node.first..node.first + node.count] {
+                    assert!(node.bounds.contains(&bvh.primitives[k].bounds()));
+                }
+            } else {
+                assert!(node.bounds.contains(&bvh.nodes[node.first].bounds));
+                assert!(node.bounds.contains(&bvh.nodes[node.first + 1].bounds));
+            }
+        }
+    }
+
+    #[test]
+    fn builds_valid_trees() {
+        let bvh = Bvh::new(triangles(500));
+        check_bounds(&bvh);
+        let mut order = bvh.order.clone();
+        order.sort();
+        assert!(order.into_iter().eq(0..500));
+        assert!(bvh.nodes.iter().all(|n| n.count <= MAX_LEAF_SIZE));
+
+        // Coincident primitives still end up in small leaves
+        let bvh = Bvh::new(vec![Pt3::new(1., 2., 3.); 100]);
+        check_bounds(&bvh);
+        assert!(bvh.nodes.iter().all(|n| n.count <= MAX_LEAF_SIZE));
+
+        let empty: Bvh<Triangle> = Bvh::new(Vec::new());
+        assert!(empty.is_empty());
+        assert!(empty.bounds().is_empty());
+        assert!(empty.raycast(&rays(1)[0]).is_none());
+        assert!(empty.nearest(&Pt3::new(0., 0., 0.)).is_none());
+    }
+
+    #[test]
+    fn casts_rays() {
+        let tris = triangles(500);
+        let bvh = Bvh::new(tris.clone());
+        let mut hits = 0;
+        for ray in rays(200) {
+            let expected = brute_raycast(&tris, &ray);
+            let found = bvh.raycast(&ray);
+            assert_eq!(found.map(|(k, _)| k), expected.map(|(k, _)| k));
+            if let Some((k, hit)) = found {
+                hits += 1;
+                assert_eq!(hit.t, expected.unwrap().1.t);
+                assert!(bvh.raycast_within(&ray, hit.t * 0.99).is_none());
+                let (j, any) = bvh.raycast_any(&ray, f32::INFINITY).unwrap();
+                assert!(tris[j].raycast(&ray).is_some());
+                assert!(any.t >= hit.t);
+                assert_eq!(bvh.raycast_any(&ray, hit.t).unwrap().0, k);
+            } else {
+                assert!(bvh.raycast_any(&ray, f32::INFINITY).is_none());
+            }
+        }
+        assert!(hits > 20);
+    }
+
+    #[test]
+    fn finds_overlaps_and_nearest_points() {
+        let tris = triangles(300);
+        let bvh = Bvh::new(tris.clone());
+        let mut seed = 3;
+        for _ in 0..50 {
+            let query = Aabb::from_center(random_pt(&mut seed, 10.), Vec3::new(1., 2., 1.5));
+            let mut found = bvh.overlapping(&query);
+            found.sort();
+            let expected: Vec<usize> = (0..tris.len())
+                .filter(|&k| tris[k].bounds().overlaps(&query))
+                .collect();
+            assert_eq!(found, expected);
+
+            let p = random_pt(&mut seed, 12.);
+            let (k, q) = bvh.nearest(&p).unwrap();
+            let d = Vec3::from(q - p).norm();
+            let expected = tris
+                .iter()
+                .map(|t| Vec3::from(t.closest_point(&p) - p).norm())
+                .fold(f32::INFINITY, f32::min);
+            assert_eq!(d, expected);
+            assert_approx_eq!(tris[k].closest_point(&p), q);
+        }
+    }
+
+    #[test]
+    fn refits_moved_primitives() {
+        let mut seed = 5;
+        let spheres: Vec<Sphere> = (0..200)
+            .map(|_| Sphere::new(random_pt(&mut seed, 10.), 0.5))
+            .collect();
+        let mut bvh = Bvh::new(spheres);
+        for s in bvh.primitives_mut() {
+            s.center = s.center + Vec3::new(20., s.center.x, 0.);
+        }
+        bvh.refit();
+        check_bounds(&bvh);
+
+        let moved = bvh.primitives().to_vec();
+        for ray in rays(100) {
+            let ray = Ray::new(ray.origin + Vec3::new(20., 0., 0.), ray.dir);
+            assert_eq!(
+                bvh.raycast(&ray).map(|(k, _)| k),
+                brute_raycast(&moved, &ray).map(|(k, _)| k)
+            );
+        }
+    }
+
+    #[test]
+    fn builds_from_triangle_soups() {
+        let positions = [
+            Vec3::new(0., 0., 0.),
+            Vec3::new(1., 0., 0.),
+            Vec3::new(0., 1., 0.),
+            Vec3::new(1., 1., 0.),
+        ];
+        let indexed = Bvh::from_indexed(&positions, &[[0, 1, 2], [1, 3, 2]]);
+        let soup = Bvh::from_soup(&[
+            positions[0],
+            positions[1],
+            positions[2],
+            positions[1],
+            positions[3],
+            positions[2],
+        ]);
+        let ray = Ray::new(Pt3::new(0.8, 0.8, 1.), -Vec3::Z);
+        assert_eq!(indexed.raycast(&ray).unwrap().0, 1);
+        assert_eq!(soup.raycast(&ray).unwrap().0, 1);
+        assert_eq!(soup.primitives().len(), 2);
+        let (k, q) = soup.nearest(&Pt3::new(0.1, 0.1, 2.)).unwrap();
+        assert_eq!(k, 0);
+        assert_approx_eq!(q, Pt3::new(0.1, 0.1, 0.));
+    }
+}
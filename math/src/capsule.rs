@@ -0,0 +1,33 @@
+use crate::{
+    aabb::Aabb,
+    geometry::{Linear, Segment},
+    pt3::Pt3,
+};
+
+/// Points within `radius` of the segment from `a` to `b`.
+#[derive(Clone, Copy, Debug)]
+pub struct Capsule {
+    pub a: Pt3,
+    pub b: Pt3,
+    pub radius: f32,
+}
+
+impl Capsule {
+    pub fn new(a: Pt3, b: Pt3, radius: f32) -> Self {
+        Self { a, b, radius }
+    }
+
+    pub fn segment(&self) -> Segment {
+        Segment::new(self.a, self.b)
+    }
+
+    pub fn contains_point(&self, p: &Pt3) -> bool {
+        self.segment().distance_to_point(p) <= self.radius
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        Aabb::new(self.a, self.a)
+            .include(&self.b)
+            .expand(self.radius)
+    }
+}
@@ -0,0 +1,132 @@
+use std::ops;
+
+use crate::{
+    dvec3::DVec3,
+    impl_op, impl_op_assign,
+    mat3::Mat3,
+    precision::is_invertible,
+    structure::{EuclideanSpace, Mat, SquareMat, VecSpace},
+};
+
+/// Double precision counterpart of `Mat3`, with the same row layout.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DMat3 {
+    pub a: DVec3,
+    pub b: DVec3,
+    pub c: DVec3,
+}
+
+impl DMat3 {
+    pub fn from_columns(a: DVec3, b: DVec3, c: DVec3) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn as_f32(&self) -> Mat3 {
+        Mat3::from_columns(self.a.as_f32(), self.b.as_f32(), self.c.as_f32())
+    }
+}
+
+impl Mat for DMat3 {
+    type Scalar = f64;
+    type Row = DVec3;
+    type Column = DVec3;
+    type Transpose = DMat3;
+
+    const ZERO: Self = Self {
+        a: DVec3::ZERO,
+        b: DVec3::ZERO,
+        c: DVec3::ZERO,
+    };
+
+    fn transpose(&self) -> Self {
+        let m = self;
+        [
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ]
+        .into()
+    }
+}
+
+impl SquareMat for DMat3 {
+    type RowColumn = DVec3;
+
+    const IDENTITY: Self = Self {
+        a: DVec3::X,
+        b: DVec3::Y,
+        c: DVec3::Z,
+    };
+
+    fn from_diagonal(d: Self::RowColumn) -> Self {
+        [[d.x, 0., 0.], [0., d.y, 0.], [0., 0., d.z]].into()
+    }
+
+    fn determinant(&self) -> f64 {
+        self.a.cross(&self.b).dot(&self.c)
+    }
+
+    fn adjugate(&self) -> Self {
+        let Self { a, b, c } = self;
+        Self::from_columns(b.cross(c), c.cross(a), a.cross(b)).transpose()
+    }
+
+    fn invert(&self) -> Option<Self> {
+        let det = self.determinant();
+        let t = self.transpose();
+
+        if is_invertible(det, &[t.a.norm(), t.b.norm(), t.c.norm()]) {
+            Some(self.adjugate() * (1. / det))
+        } else {
+            None
+        }
+    }
+
+    fn norm_1(&self) -> f64 {
+        let t = self.transpose();
+        [t.a, t.b, t.c]
+            .iter()
+            .map(|v| v.x.abs() + v.y.abs() + v.z.abs())
+            .fold(0., f64::max)
+    }
+}
+
+impl_op!(DMat3 : DMat3, ops::Mul { fn mul |lhs: &DMat3, rhs: &DMat3| {
+    let mut m = DMat3::ZERO;
+    for i in 0..3 {
+        for j in 0..3 {
+            for k in 0..3 {
+                m[i][j] += lhs[i][k] * rhs[k][j];
+            }
+        }
+    }
+    m
+}});
+
+impl_op_assign!(DMat3, DMat3, ops::MulAssign { fn mul_assign |lhs: &mut DMat3, rhs: &DMat3| {
+    *lhs = *lhs * rhs;
+}});
+
+impl_op!(DMat3 : DVec3 => DVec3, ops::Mul { fn mul |lhs: &DMat3, rhs: &DVec3| {
+    DVec3::new(lhs.a.dot(rhs), lhs.b.dot(rhs), lhs.c.dot(rhs))
+}});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculates_inverse() {
+        let m: DMat3 = [[2., 0., -1.], [5., 1., 0.], [0., 1., 3.]].into();
+        let inv = m.invert().unwrap();
+        assert_eq!(inv, [[3., -1., 1.], [-15., 6., -5.], [5., -2., 2.]].into());
+        assert_eq!(m * inv, DMat3::IDENTITY);
+    }
+
+    #[test]
+    fn converts_precision() {
+        let m = Mat3::from_angle_y(0.7);
+        assert_eq!(DMat3::from(m).as_f32(), m);
+    }
+}
@@ -0,0 +1,221 @@
+use std::ops;
+
+use crate::{
+    dvec3::DVec3,
+    dvec4::DVec4,
+    impl_op,
+    mat4::Mat4,
+    precision::is_invertible,
+    structure::{EuclideanSpace, Mat, SquareMat, VecSpace},
+};
+
+/// Double precision counterpart of `Mat4`, with the same row layout. It
+/// covers the `SquareMat` algebra for composing world-space transforms;
+/// projections, view matrices and `Transform4` are single precision only,
+/// so convert with `as_f32` once the result is camera-relative.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DMat4 {
+    pub a: DVec4,
+    pub b: DVec4,
+    pub c: DVec4,
+    pub d: DVec4,
+}
+
+impl DMat4 {
+    pub fn from_columns(a: DVec4, b: DVec4, c: DVec4, d: DVec4) -> Self {
+        Self { a, b, c, d }
+    }
+
+    /// Whether the bottom row is exactly `[0, 0, 0, 1]`.
+    pub fn is_affine(&self) -> bool {
+        let DVec4 { x, y, z, w } = self.d;
+        x == 0. && y == 0. && z == 0. && w == 1.
+    }
+
+    pub fn as_f32(&self) -> Mat4 {
+        Mat4::from_columns(
+            self.a.as_f32(),
+            self.b.as_f32(),
+            self.c.as_f32(),
+            self.d.as_f32(),
+        )
+    }
+
+    // See `Mat4::partials`
+    fn partials(&self) -> (DVec3, DVec3, DVec3, DVec3) {
+        let a = self.a.xyz();
+        let b = self.b.xyz();
+        let c = self.c.xyz();
+        let d = self.d.xyz();
+
+        let s = a.cross(&b);
+        let t = c.cross(&d);
+        let u = a * self[1][3] - b * self[0][3];
+        let v = c * self[3][3] - d * self[2][3];
+
+        (s, t, u, v)
+    }
+}
+
+impl Mat for DMat4 {
+    type Scalar = f64;
+    type Row = DVec4;
+    type Column = DVec4;
+    type Transpose = DMat4;
+
+    const ZERO: Self = Self {
+        a: DVec4::ZERO,
+        b: DVec4::ZERO,
+        c: DVec4::ZERO,
+        d: DVec4::ZERO,
+    };
+
+    fn transpose(&self) -> Self::Transpose {
+        let m = self;
+        [
+            [m[0][0], m[1][0], m[2][0], m[3][0]],
+            [m[0][1], m[1][1], m[2][1], m[3][1]],
+            [m[0][2], m[1][2], m[2][2], m[3][2]],
+            [m[0][3], m[1][3], m[2][3], m[3][3]],
+        ]
+        .into()
+    }
+}
+
+impl SquareMat for DMat4 {
+    type RowColumn = DVec4;
+
+    const IDENTITY: Self = Self {
+        a: DVec4::X,
+        b: DVec4::Y,
+        c: DVec4::Z,
+        d: DVec4::W,
+    };
+
+    fn from_diagonal(d: Self::RowColumn) -> Self {
+        Self {
+            a: [d.x, 0., 0., 0.].into(),
+            b: [0., d.y, 0., 0.].into(),
+            c: [0., 0., d.z, 0.].into(),
+            d: [0., 0., 0., d.w].into(),
+        }
+    }
+
+    fn determinant(&self) -> f64 {
+        let (s, t, u, v) = self.partials();
+        s.dot(&v) + t.dot(&u)
+    }
+
+    fn adjugate(&self) -> Self {
+        let a = self.a.xyz();
+        let b = self.b.xyz();
+        let c = self.c.xyz();
+        let d = self.d.xyz();
+
+        let x = self[0][3];
+        let y = self[1][3];
+        let z = self[2][3];
+        let w = self[3][3];
+
+        let (s, t, u, v) = self.partials();
+
+        let r0 = b.cross(&v) + t * y;
+        let r1 = v.cross(&a) - t * x;
+        let r2 = d.cross(&u) + s * w;
+        let r3 = u.cross(&c) - s * z;
+
+        Self::from_columns(
+            r0.extend(-b.dot(&t)),
+            r1.extend(a.dot(&t)),
+            r2.extend(-d.dot(&s)),
+            r3.extend(c.dot(&s)),
+        )
+        .transpose()
+    }
+
+    fn invert(&self) -> Option<Self> {
+        let det = self.determinant();
+        let t = self.transpose();
+        let mut norms = [t.a.norm(), t.b.norm(), t.c.norm(), t.d.norm()];
+        if self.is_affine() {
+            // The translation does not affect invertibility, only the
+            // linear part is weighed
+            norms[3] = 1.;
+        }
+
+        if is_invertible(det, &norms) {
+            Some(self.adjugate() * (1. / det))
+        } else {
+            None
+        }
+    }
+
+    fn norm_1(&self) -> f64 {
+        let t = self.transpose();
+        [t.a, t.b, t.c, t.d]
+            .iter()
+            .map(|v| v.x.abs() + v.y.abs() + v.z.abs() + v.w.abs())
+            .fold(0., f64::max)
+    }
+}
+
+impl_op!(DMat4 : DMat4, ops::Mul { fn mul |a: &DMat4, b: &DMat4| {
+    let mut m = DMat4::ZERO;
+    for i in 0..4 {
+        for j in 0..4 {
+            for k in 0..4 {
+                m[i][j] += a[i][k] * b[k][j];
+            }
+        }
+    }
+    m
+}});
+
+impl_op!(DMat4 : DVec4 => DVec4, ops::Mul { fn mul |m: &DMat4, v: &DVec4| {
+    DVec4::new(v.dot(&m.a), v.dot(&m.b), v.dot(&m.c), v.dot(&m.d))
+}});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverts() {
+        let m: DMat4 = [
+            [6., 1., 2., 4.],
+            [4., 3., 3., 2.],
+            [2., 5., 5., 6.],
+            [8., 7., 6., 3.],
+        ]
+        .into();
+        assert_eq!(m.determinant(), 50.);
+        assert_eq!(m * m.invert().unwrap(), DMat4::IDENTITY);
+    }
+
+    #[test]
+    fn inverts_far_from_origin() {
+        // A translation 10^7 units out loses the fractional part in f32
+        let m: DMat4 = [
+            [1., 0., 0., 1e7 + 0.25],
+            [0., 1., 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., 0., 1.],
+        ]
+        .into();
+        let p = DVec4::new(1e7 + 0.75, 0., 0., 1.);
+        assert_eq!((m.invert().unwrap() * p).x, 0.5);
+
+        // Beyond 1e12 the translation column alone would fail the ratio
+        let mut far = m;
+        far.a.w = 1e15;
+        assert_eq!(far.invert().unwrap().a.w, -1e15);
+        assert_eq!(m.as_f32()[0][3], 1e7);
+    }
+
+    #[test]
+    fn converts_precision() {
+        let m = Mat4::perspective_rh(1.2, 1.5, 0.1, 100.);
+        assert_eq!(DMat4::from(m).as_f32(), m);
+    }
+}
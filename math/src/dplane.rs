@@ -0,0 +1,53 @@
+use crate::{dpt3::DPt3, dvec3::DVec3, impl_conversions, plane::Plane, structure::EuclideanSpace};
+
+/// Double precision counterpart of `Plane`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DPlane {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub d: f64,
+}
+
+impl DPlane {
+    pub fn new(x: f64, y: f64, z: f64, d: f64) -> DPlane {
+        DPlane { x, y, z, d }
+    }
+
+    pub fn normal(&self) -> DVec3 {
+        DVec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn as_f32(&self) -> Plane {
+        Plane::new(self.x as f32, self.y as f32, self.z as f32, self.d as f32)
+    }
+}
+
+impl EuclideanSpace<DVec3> for DPlane {
+    type Scalar = f64;
+
+    fn dot(&self, v: &DVec3) -> f64 {
+        self.x * v.x + self.y * v.y + self.z * v.z
+    }
+}
+
+impl EuclideanSpace<DPt3> for DPlane {
+    type Scalar = f64;
+
+    fn dot(&self, p: &DPt3) -> f64 {
+        self.x * p.x + self.y * p.y + self.z * p.z + self.d
+    }
+}
+
+impl_conversions!(DPlane => [f64; 4], |f: &DPlane| {
+    [f.x, f.y, f.z, f.d]
+});
+
+impl_conversions!([f64; 4] => DPlane, |a: &[f64; 4]| {
+    DPlane::new(a[0], a[1], a[2], a[3])
+});
+
+impl_conversions!(Plane => DPlane, |f: &Plane| {
+    DPlane::new(f.x.into(), f.y.into(), f.z.into(), f.d.into())
+});
@@ -0,0 +1,33 @@
+use crate::{
+    pt3::Pt3,
+    structure::{EuclideanSpace, VecSpace},
+};
+
+/// Double precision counterpart of `Pt3`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DPt3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl DPt3 {
+    pub fn new(x: f64, y: f64, z: f64) -> DPt3 {
+        DPt3 { x, y, z }
+    }
+
+    pub fn as_f32(&self) -> Pt3 {
+        Pt3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl VecSpace for DPt3 {}
+
+impl EuclideanSpace<DPt3> for DPt3 {
+    type Scalar = f64;
+
+    fn dot(&self, other: &DPt3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+}
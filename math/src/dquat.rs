@@ -0,0 +1,262 @@
+use std::ops;
+
+use crate::{
+    dmat3::DMat3,
+    dvec3::DVec3,
+    impl_algebraic_ops, impl_conversions, impl_index_ops, impl_op,
+    quat::Quat,
+    structure::{EuclideanSpace, VecSpace},
+};
+
+/// Double precision counterpart of `Quat`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DQuat {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl DQuat {
+    pub const IDENTITY: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+        w: 1.,
+    };
+
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> DQuat {
+        DQuat { x, y, z, w }
+    }
+
+    pub fn from_vec_part(v: DVec3, w: f64) -> DQuat {
+        DQuat::new(v.x, v.y, v.z, w)
+    }
+
+    pub fn get_vec_part(&self) -> DVec3 {
+        DVec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn from_axis_angle(a: DVec3, t: f64) -> DQuat {
+        // Warning: `a` has to be normalized
+        let (s, c) = (t * 0.5).sin_cos();
+        DQuat::from_vec_part(a * s, c)
+    }
+
+    /// Shortest rotation taking direction `a` onto direction `b`.
+    pub fn from_rotation_arc(a: DVec3, b: DVec3) -> DQuat {
+        let a = a.normalize();
+        let b = b.normalize();
+        let c = a.dot(&b);
+        if c < -1. + 1e-12 {
+            // Antiparallel, see `Quat::from_rotation_arc`
+            let other = if a.x.abs() < 0.9 { DVec3::X } else { DVec3::Y };
+            let axis = a.cross(&other).normalize();
+            DQuat::from_vec_part(axis, 0.)
+        } else {
+            DQuat::from_vec_part(a.cross(&b), 1. + c).normalize()
+        }
+    }
+
+    pub fn conjugate(&self) -> DQuat {
+        DQuat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(&self) -> DQuat {
+        self.conjugate() / self.norm2()
+    }
+
+    pub fn rotate(&self, v: &DVec3) -> DVec3 {
+        v * self
+    }
+
+    /// Normalized linear interpolation along the shorter arc.
+    pub fn nlerp(&self, other: &DQuat, t: f64) -> DQuat {
+        let other = if self.dot(other) < 0. { -other } else { *other };
+        (self * (1. - t) + other * t).normalize()
+    }
+
+    /// Spherical linear interpolation along the shorter arc.
+    pub fn slerp(&self, other: &DQuat, t: f64) -> DQuat {
+        // Warning: both quaternions have to be normalized
+        let mut c = self.dot(other);
+        let mut other = *other;
+        if c < 0. {
+            c = -c;
+            other = -other;
+        }
+        if c > 1. - 1e-12 {
+            // Nearly identical rotations, sin(theta) would vanish
+            return self.nlerp(&other, t);
+        }
+        let theta = c.acos();
+        let inv_sin = 1. / theta.sin();
+        let a = ((1. - t) * theta).sin() * inv_sin;
+        let b = (t * theta).sin() * inv_sin;
+        self * a + other * b
+    }
+
+    pub fn as_f32(&self) -> Quat {
+        Quat::new(self.x as f32, self.y as f32, self.z as f32, self.w as f32)
+    }
+}
+
+impl EuclideanSpace<Self> for DQuat {
+    type Scalar = f64;
+
+    fn dot(&self, other: &Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+}
+
+impl VecSpace for DQuat {}
+
+impl_algebraic_ops!(DQuat { x, y, z, w }, 4, f64);
+
+impl_index_ops!(DQuat { 0 => x, 1 => y, 2 => z, 3 => w } => f64);
+
+impl_conversions!(DQuat => [f64; 4], |from: &DQuat| {
+    [from.x, from.y, from.z, from.w]
+});
+
+impl_conversions!([f64; 4] => DQuat, |from: &[f64; 4]| {
+    DQuat::new(from[0], from[1], from[2], from[3])
+});
+
+impl_conversions!(Quat => DQuat, |q: &Quat| {
+    DQuat::new(q.x.into(), q.y.into(), q.z.into(), q.w.into())
+});
+
+impl_op!(DQuat : DQuat, ops::Mul { fn mul |lhs: &DQuat, rhs: &DQuat| {
+    let DQuat { x: x1, y: y1, z: z1, w: w1 } = *lhs;
+    let DQuat { x: x2, y: y2, z: z2, w: w2 } = *rhs;
+    DQuat::new(
+        w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+        w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+        w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+        w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
+    ) }
+});
+
+impl_op!(DVec3 : DQuat, ops::Mul { fn mul |v: &DVec3, q: &DQuat| {
+    let b = q.get_vec_part();
+    let b2 = b.norm2();
+    v * (q.w * q.w - b2) + b * (v.dot(&b) * 2.) + b.cross(v) * (q.w * 2.)
+}});
+
+impl_conversions!(DQuat => DMat3, |q: &DQuat| {
+    let DQuat { x, y, z, w } = *q;
+    let x2 = x * x;
+    let y2 = y * y;
+    let z2 = z * z;
+    let xy = x * y;
+    let xz = x * z;
+    let yz = y * z;
+    let wx = w * x;
+    let wy = w * y;
+    let wz = w * z;
+    [
+        [1. - 2. * (y2 + z2), 2. * (xy - wz), 2. * (xz + wy)],
+        [2. * (xy + wz), 1. - 2. * (x2 + z2), 2. * (yz - wx)],
+        [2. * (xz - wy), 2. * (yz + wx), 1. - 2. * (x2 + y2)],
+    ]
+    .into()
+});
+
+impl_conversions!(DMat3 => DQuat, |m: &DMat3| {
+    let m00 = m[0][0];
+    let m11 = m[1][1];
+    let m22 = m[2][2];
+    let sum = m00 + m11 + m22;
+
+    if sum > 0. {
+        let w = (sum + 1.).sqrt() * 0.5;
+        let f = 0.25 / w;
+        let x = (m[2][1] - m[1][2]) * f;
+        let y = (m[0][2] - m[2][0]) * f;
+        let z = (m[1][0] - m[0][1]) * f;
+        DQuat::new(x, y, z, w)
+    } else if (m00 > m11) && (m00 > m22) {
+        // x is largest
+        let x = (m00 - m11 - m22 + 1.).sqrt() * 0.5;
+        let f = 0.25 / x;
+        let y = (m[1][0] + m[0][1]) * f;
+        let z = (m[0][2] + m[2][0]) * f;
+        let w = (m[2][1] - m[1][2]) * f;
+        DQuat::new(x, y, z, w)
+    } else if m11 > m22 {
+        // y is largest
+        let y = (m11 - m00 - m22 + 1.).sqrt() * 0.5;
+        let f = 0.25 / y;
+        let x = (m[1][0] + m[0][1]) * f;
+        let z = (m[2][1] + m[1][2]) * f;
+        let w = (m[0][2] - m[2][0]) * f;
+        DQuat::new(x, y, z, w)
+    } else {
+        // z is largest
+        let z = (m22 - m00 - m11 + 1.).sqrt() * 0.5;
+        let f = 0.25 / z;
+        let x = (m[0][2] + m[2][0]) * f;
+        let y = (m[2][1] + m[1][2]) * f;
+        let w = (m[1][0] - m[0][1]) * f;
+        DQuat::new(x, y, z, w)
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use crate::{mat3::Mat3, vec3::Vec3};
+
+    use super::*;
+
+    #[test]
+    fn matches_single_precision() {
+        let q = Quat::from_axis_angle(Vec3::new(1., 2., 3.).normalize(), 0.8);
+        let dq = DQuat::from(q);
+        let v = Vec3::new(-1., 0.5, 2.);
+        assert_eq!(dq.rotate(&v.into()).as_f32(), q.rotate(&v));
+        assert_eq!(DMat3::from(dq).as_f32(), Mat3::from(q));
+        assert_eq!(dq.as_f32(), q);
+    }
+
+    #[test]
+    fn interpolates_like_single_precision() {
+        let a = Quat::from_axis_angle(Vec3::Y, 0.2);
+        let b = -Quat::from_axis_angle(Vec3::new(1., 2., 3.).normalize(), 1.4);
+        let (da, db) = (DQuat::from(a), DQuat::from(b));
+        for t in [0., 0.25, 0.5, 1.] {
+            assert_eq!(da.slerp(&db, t).as_f32(), a.slerp(&b, t as f32));
+            assert_eq!(da.nlerp(&db, t).as_f32(), a.nlerp(&b, t as f32));
+        }
+        assert_eq!(da.slerp(&da, 0.5).as_f32(), a);
+    }
+
+    #[test]
+    fn rotates_arcs() {
+        let q = DQuat::from_rotation_arc(DVec3::X, DVec3::new(0., 3., 0.));
+        assert_eq!(q.rotate(&DVec3::X), DVec3::Y);
+        let flip = DQuat::from_rotation_arc(DVec3::X, -DVec3::X);
+        assert_eq!(flip.rotate(&DVec3::X), -DVec3::X);
+    }
+
+    #[test]
+    fn round_trips_dmat3() {
+        for q in [
+            Quat::from_axis_angle(Vec3::new(1., 2., 3.).normalize(), 0.8),
+            Quat::from_axis_angle(Vec3::X, 3.),
+            Quat::from_axis_angle(Vec3::Y, 3.),
+            Quat::from_axis_angle(Vec3::Z, 3.),
+        ] {
+            let dq = DQuat::from(q).normalize();
+            let back = DQuat::from(DMat3::from(dq));
+            assert!(back == dq || back == -dq);
+        }
+    }
+
+    #[test]
+    fn inverts() {
+        let q = DQuat::new(1., 2., 3., 4.);
+        assert_eq!(q * q.inverse(), DQuat::IDENTITY);
+    }
+}
@@ -0,0 +1,371 @@
+use std::ops;
+
+use crate::{
+    impl_conversions, impl_op,
+    mat3::Mat3,
+    mat4::Mat4,
+    pt3::Pt3,
+    quat::Quat,
+    structure::{EuclideanSpace, VecSpace},
+    transform4::Transform4,
+    vec3::Vec3,
+};
+
+/// Rigid transform `real + eps * dual` with `eps^2 = 0`.
+///
+/// A unit dual quaternion stores the rotation in `real` and half the
+/// translation premultiplied onto the rotation in `dual`, so it applies the
+/// rotation first and then the translation.
+#[derive(Clone, Copy, Debug)]
+pub struct DualQuat {
+    pub real: Quat,
+    pub dual: Quat,
+}
+
+impl DualQuat {
+    pub const ZERO: Self = Self {
+        real: Quat::ZERO,
+        dual: Quat::ZERO,
+    };
+
+    pub const IDENTITY: Self = Self {
+        real: Quat::IDENTITY,
+        dual: Quat::ZERO,
+    };
+
+    pub fn new(real: Quat, dual: Quat) -> Self {
+        Self { real, dual }
+    }
+
+    pub fn from_rotation_translation(r: Quat, t: Vec3) -> Self {
+        // Warning: `r` has to be normalized
+        Self::new(r, Quat::from_vec_part(t, 0.) * r * 0.5)
+    }
+
+    pub fn from_rotation(r: Quat) -> Self {
+        Self::from_rotation_translation(r, Vec3::ZERO)
+    }
+
+    pub fn from_translation(t: Vec3) -> Self {
+        Self::from_rotation_translation(Quat::IDENTITY, t)
+    }
+
+    pub fn rotation(&self) -> Quat {
+        self.real
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        (self.dual * self.real.conjugate() * 2.).get_vec_part()
+    }
+
+    /// Applies the conjugate to both parts, `q* = r* + eps d*`.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.real.conjugate(), self.dual.conjugate())
+    }
+
+    pub fn inverse(&self) -> Self {
+        // Warning: `self` has to be normalized
+        self.conjugate()
+    }
+
+    /// Projects onto the unit dual quaternions: the real part gets unit
+    /// length and the dual part is made orthogonal to it.
+    pub fn normalize(&self) -> Self {
+        let n = self.real.norm();
+        let real = self.real / n;
+        let dual = self.dual / n;
+        Self::new(real, dual - real * real.dot(&dual))
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.real.dot(&other.real)
+    }
+
+    pub fn transform_point(&self, p: &Pt3) -> Pt3 {
+        (self.real.rotate(&Vec3::from(p)) + self.translation()).into()
+    }
+
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        self.real.rotate(v)
+    }
+
+    /// Screw linear interpolation. Moves along the shortest screw motion
+    /// with constant linear and angular velocity.
+    pub fn sclerp(&self, other: &Self, t: f32) -> Self {
+        // Warning: both dual quaternions have to be normalized
+        let other = if self.dot(other) < 0. {
+            -*other
+        } else {
+            *other
+        };
+        let diff = self.conjugate() * other;
+        self * diff.pow(t)
+    }
+
+    /// Dual quaternion linear blending, see Kavan et al., "Skinning with
+    /// Dual Quaternions". Weights need not sum to one. Falls back to the
+    /// identity when there is nothing to blend, either because the slice is
+    /// empty or because the weights cancel out.
+    pub fn blend(transforms: &[(DualQuat, f32)]) -> Self {
+        let Some((pivot, _)) = transforms.first() else {
+            return Self::IDENTITY;
+        };
+        let sum = transforms.iter().fold(Self::ZERO, |acc, (q, w)| {
+            // Keep every rotation in the hemisphere of the first one
+            let w = if pivot.dot(q) < 0. { -w } else { *w };
+            acc + q * w
+        });
+        if sum.real.norm() <= f32::EPSILON {
+            return Self::IDENTITY;
+        }
+        sum.normalize()
+    }
+
+    // Raises a unit dual quaternion to a real power through its screw
+    // parameters: angle `theta`, pitch `d`, axis `l` and moment `m`
+    fn pow(&self, t: f32) -> Self {
+        let w = self.real.w.clamp(-1., 1.);
+        let v = self.real.get_vec_part();
+        let sin_half = v.norm();
+        if sin_half < 1e-6 {
+            // Pure translation, the screw degenerates to a line
+            return Self::new(Quat::IDENTITY, self.dual * t);
+        }
+        let theta = 2. * sin_half.atan2(w);
+        let l = v / sin_half;
+        let d = -2. * self.dual.w / sin_half;
+        let m = (self.dual.get_vec_part() - l * (d * 0.5 * w)) / sin_half;
+
+        let (s, c) = (t * theta * 0.5).sin_cos();
+        let td = t * d;
+        Self::new(
+            Quat::from_vec_part(l * s, c),
+            Quat::from_vec_part(m * s + l * (td * 0.5 * c), -td * 0.5 * s),
+        )
+    }
+}
+
+impl_op!(DualQuat : DualQuat, ops::Mul { fn mul |lhs: &DualQuat, rhs: &DualQuat| {
+    DualQuat::new(lhs.real * rhs.real, lhs.real * rhs.dual + lhs.dual * rhs.real)
+}});
+
+impl_op!(DualQuat : DualQuat, ops::Add { fn add |lhs: &DualQuat, rhs: &DualQuat| {
+    DualQuat::new(lhs.real + rhs.real, lhs.dual + rhs.dual)
+}});
+
+impl_op!(DualQuat : f32, ops::Mul { fn mul |lhs: &DualQuat, rhs: &f32| {
+    DualQuat::new(lhs.real * *rhs, lhs.dual * *rhs)
+}});
+
+impl_op!(DualQuat, ops::Neg { fn neg |lhs: &DualQuat| {
+    DualQuat::new(-lhs.real, -lhs.dual)
+}});
+
+impl_conversions!(DualQuat => [[f32; 4]; 2], |q: &DualQuat| {
+    [q.real.into(), q.dual.into()]
+});
+
+impl_conversions!([[f32; 4]; 2] => DualQuat, |a: &[[f32; 4]; 2]| {
+    DualQuat::new(a[0].into(), a[1].into())
+});
+
+impl_conversions!(DualQuat => Mat4, |q: &DualQuat| {
+    let r = Mat3::from(q.real);
+    let t = q.translation();
+    Transform4::new(
+        r[0][0], r[0][1], r[0][2], t.x, //
+        r[1][0], r[1][1], r[1][2], t.y, //
+        r[2][0], r[2][1], r[2][2], t.z,
+    )
+});
+
+impl_conversions!(Mat4 => DualQuat, |m: &Mat4| {
+    // Warning: `m` has to be a rigid transform
+    let r = Mat3::new(
+        m[0][0], m[0][1], m[0][2], //
+        m[1][0], m[1][1], m[1][2], //
+        m[2][0], m[2][1], m[2][2],
+    );
+    DualQuat::from_rotation_translation(Quat::from(r).normalize(), m.get_translation())
+});
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use float_cmp::approx_eq;
+
+    use crate::{assert_approx_eq, precision::PRECISION, structure::SquareMat};
+
+    use super::*;
+
+    fn sample() -> DualQuat {
+        DualQuat::from_rotation_translation(
+            Quat::from_axis_angle(Vec3::new(1., 2., -1.).normalize(), 1.3),
+            Vec3::new(4., -1., 2.),
+        )
+    }
+
+    #[test]
+    fn recovers_rotation_and_translation() {
+        let r = Quat::from_axis_angle(Vec3::Y, 0.7);
+        let t = Vec3::new(1., 2., 3.);
+        let q = DualQuat::from_rotation_translation(r, t);
+        assert_eq!(q.rotation(), r);
+        assert_eq!(q.translation(), t);
+    }
+
+    #[test]
+    fn transforms_points_and_vectors() {
+        let q = DualQuat::from_rotation_translation(
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2),
+            Vec3::new(1., 0., 0.),
+        );
+        let p = q.transform_point(&Pt3::new(1., 0., 5.));
+        assert_approx_eq!(p, Pt3::new(1., 1., 5.));
+        assert_eq!(q.transform_vector(&Vec3::X), Vec3::Y);
+    }
+
+    #[test]
+    fn composes() {
+        let a = sample();
+        let b = DualQuat::from_rotation_translation(
+            Quat::from_axis_angle(Vec3::X, -0.4),
+            Vec3::new(0., 3., 1.),
+        );
+        let p = Pt3::new(1., 2., 3.);
+        assert_eq!(
+            Vec3::from((a * b).transform_point(&p)),
+            Vec3::from(a.transform_point(&b.transform_point(&p)))
+        );
+    }
+
+    #[test]
+    fn inverts() {
+        let q = sample();
+        assert_eq!(q * q.inverse(), DualQuat::IDENTITY);
+        let p = Pt3::new(1., 2., 3.);
+        assert_eq!(
+            Vec3::from(q.inverse().transform_point(&q.transform_point(&p))),
+            Vec3::from(p)
+        );
+    }
+
+    #[test]
+    fn normalizes() {
+        let q = sample();
+        let scaled = DualQuat::new(q.real * 3., q.dual * 3. + q.real * 0.1);
+        let n = scaled.normalize();
+        assert!(approx_eq!(f32, n.real.norm(), 1., PRECISION));
+        assert!(approx_eq!(f32, n.real.dot(&n.dual), 0., PRECISION));
+    }
+
+    #[test]
+    fn round_trips_mat4() {
+        let q = sample();
+        let m = Mat4::from(q);
+        let p = Pt3::new(1., 2., 3.);
+        let mp = m * Vec3::from(p).extend(1.);
+        assert_eq!(mp.xyz(), Vec3::from(q.transform_point(&p)));
+        let back = DualQuat::from(m);
+        assert!(back == q || back == -q);
+        assert_eq!(Mat4::from(DualQuat::IDENTITY), Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn sclerps_endpoints() {
+        let a = DualQuat::IDENTITY;
+        let b = sample();
+        assert_eq!(a.sclerp(&b, 0.), a);
+        assert_eq!(a.sclerp(&b, 1.), b);
+    }
+
+    #[test]
+    fn sclerps_at_constant_screw_velocity() {
+        // A quarter turn about z combined with a climb along z is a pure
+        // screw, so the midpoint is half the turn and half the climb
+        let a = DualQuat::IDENTITY;
+        let b = DualQuat::from_rotation_translation(
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2),
+            Vec3::new(0., 0., 2.),
+        );
+        let mid = a.sclerp(&b, 0.5);
+        assert_eq!(
+            mid.rotation(),
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 * 0.5)
+        );
+        assert_eq!(mid.translation(), Vec3::new(0., 0., 1.));
+    }
+
+    #[test]
+    fn sclerps_pure_translation() {
+        let a = DualQuat::from_translation(Vec3::new(1., 0., 0.));
+        let b = DualQuat::from_translation(Vec3::new(3., 4., 0.));
+        assert_eq!(a.sclerp(&b, 0.5).translation(), Vec3::new(2., 2., 0.));
+    }
+
+    #[test]
+    fn sclerp_keeps_moving_points_on_the_screw() {
+        // Rotating about an axis offset from the origin should keep points
+        // at constant distance from that axis throughout the motion
+        let pivot = Vec3::new(2., 0., 0.);
+        let r = Quat::from_axis_angle(Vec3::Z, 2.);
+        let b = DualQuat::from_translation(pivot)
+            * DualQuat::from_rotation(r)
+            * DualQuat::from_translation(-pivot);
+        for t in [0.25, 0.5, 0.75] {
+            let p = Vec3::from(
+                DualQuat::IDENTITY
+                    .sclerp(&b, t)
+                    .transform_point(&Pt3::new(0., 0., 0.)),
+            );
+            assert!(approx_eq!(f32, (p - pivot).norm(), 2., PRECISION));
+        }
+    }
+
+    #[test]
+    fn blends() {
+        let a = DualQuat::from_rotation(Quat::from_axis_angle(Vec3::Z, 0.));
+        let b = DualQuat::from_rotation(Quat::from_axis_angle(Vec3::Z, FRAC_PI_2));
+        let mid = DualQuat::blend(&[(a, 1.), (b, 1.)]);
+        assert_eq!(
+            mid.rotation(),
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 * 0.5)
+        );
+        assert_eq!(DualQuat::blend(&[(b, 0.3)]), b);
+        assert_eq!(DualQuat::blend(&[]), DualQuat::IDENTITY);
+    }
+
+    #[test]
+    fn blend_falls_back_to_identity_for_cancelling_weights() {
+        let a = sample();
+        let b = DualQuat::from_rotation(Quat::from_axis_angle(Vec3::Z, FRAC_PI_2));
+        assert_eq!(DualQuat::blend(&[(a, 0.)]), DualQuat::IDENTITY);
+        assert_eq!(DualQuat::blend(&[(a, 1.), (a, -1.)]), DualQuat::IDENTITY);
+        assert_eq!(DualQuat::blend(&[(a, 0.), (b, 0.)]), DualQuat::IDENTITY);
+    }
+
+    #[test]
+    fn blend_handles_antipodal_rotations() {
+        let a = sample();
+        let mid = DualQuat::blend(&[(a, 0.5), (-a, 0.5)]);
+        assert!(mid == a || mid == -a);
+    }
+
+    #[test]
+    fn blend_avoids_candy_wrapper_collapse() {
+        // Linear blend skinning of two opposite twists shrinks the point to
+        // the axis; blending dual quaternions keeps it on the unit circle
+        let a = DualQuat::from_rotation(Quat::from_axis_angle(Vec3::X, 1.5));
+        let b = DualQuat::from_rotation(Quat::from_axis_angle(Vec3::X, -1.5));
+        let p = DualQuat::blend(&[(a, 0.5), (b, 0.5)]).transform_point(&Pt3::new(0., 1., 0.));
+        assert!(approx_eq!(f32, Vec3::from(p).norm(), 1., PRECISION));
+    }
+
+    #[test]
+    fn matches_mat4_inverse() {
+        let q = sample();
+        let m = Mat4::from(q);
+        assert_eq!(Mat4::from(q.inverse()), SquareMat::invert(&m).unwrap());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    dvec4::DVec4,
+    structure::{EuclideanSpace, ProjectionSpace, VecSpace},
+    vec3::Vec3,
+};
+
+/// Double precision counterpart of `Vec3`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DVec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl DVec3 {
+    pub const ZERO: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+
+    pub const X: Self = Self {
+        x: 1.,
+        y: 0.,
+        z: 0.,
+    };
+
+    pub const Y: Self = Self {
+        x: 0.,
+        y: 1.,
+        z: 0.,
+    };
+
+    pub const Z: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 1.,
+    };
+
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn cross(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    pub fn extend(&self, w: f64) -> DVec4 {
+        DVec4::new(self.x, self.y, self.z, w)
+    }
+
+    pub fn as_f32(&self) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl EuclideanSpace<Self> for DVec3 {
+    type Scalar = f64;
+
+    fn dot(&self, other: &Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+}
+
+impl VecSpace for DVec3 {}
+
+impl ProjectionSpace<DVec3> for DVec3 {
+    fn project(&self, v: &DVec3) -> DVec3 {
+        self * (v.dot(self) / self.norm2())
+    }
+
+    fn reject(&self, v: &DVec3) -> DVec3 {
+        v - self.project(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_works() {
+        assert_eq!(
+            DVec3::new(1., 2., 3.).cross(&DVec3::new(4., 5., 6.)),
+            DVec3::new(-3., 6., -3.)
+        );
+    }
+
+    #[test]
+    fn keeps_precision_lost_in_f32() {
+        // 2^24 + 1 is the first integer an f32 cannot represent
+        let v = DVec3::new(16_777_217., 0., 0.);
+        assert_eq!((v - DVec3::new(16_777_216., 0., 0.)).x, 1.);
+        assert_eq!(v.as_f32().x, 16_777_216.);
+    }
+
+    #[test]
+    fn converts_precision() {
+        let v = Vec3::new(1.5, -2., 3.25);
+        assert_eq!(DVec3::from(v), DVec3::new(1.5, -2., 3.25));
+        assert_eq!(DVec3::from(v).as_f32(), v);
+    }
+
+    #[test]
+    fn normalizes() {
+        let v = DVec3::new(1., 2., 3.);
+        assert_eq!(v.normalize(), v / 14_f64.sqrt());
+        assert_eq!(v.normalize().norm(), 1.);
+    }
+
+    #[test]
+    fn projects() {
+        assert_eq!(
+            DVec3::new(4., 5., 6.).project(&DVec3::new(1., 2., 3.)),
+            DVec3::new(4., 5., 6.) * (32. / 77.)
+        );
+    }
+}
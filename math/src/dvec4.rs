@@ -0,0 +1,74 @@
+use crate::{
+    dvec3::DVec3,
+    structure::{EuclideanSpace, VecSpace},
+    vec4::Vec4,
+};
+
+/// Double precision counterpart of `Vec4`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct DVec4 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl DVec4 {
+    pub const ZERO: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+        w: 0.,
+    };
+
+    pub const X: Self = Self {
+        x: 1.,
+        y: 0.,
+        z: 0.,
+        w: 0.,
+    };
+
+    pub const Y: Self = Self {
+        x: 0.,
+        y: 1.,
+        z: 0.,
+        w: 0.,
+    };
+
+    pub const Z: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 1.,
+        w: 0.,
+    };
+
+    pub const W: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+        w: 1.,
+    };
+
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn xyz(&self) -> DVec3 {
+        DVec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn as_f32(&self) -> Vec4 {
+        Vec4::new(self.x as f32, self.y as f32, self.z as f32, self.w as f32)
+    }
+}
+
+impl VecSpace for DVec4 {}
+
+impl EuclideanSpace<Self> for DVec4 {
+    type Scalar = f64;
+
+    fn dot(&self, other: &Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{impl_conversions, mat3::Mat3, quat::Quat, vec3::Vec3};
+
+/// Axis sequence of an `EulerAngles` rotation.
+///
+/// The rotations are extrinsic: `XYZ` rotates about the world x axis first,
+/// then world y, then world z, so the matrix is `Rz * Ry * Rx`. This is the
+/// same as intrinsic `ZYX`. The last six orders repeat their first axis and
+/// are the proper Euler angles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
+}
+
+impl EulerOrder {
+    pub const ALL: [EulerOrder; 12] = [
+        EulerOrder::XYZ,
+        EulerOrder::XZY,
+        EulerOrder::YXZ,
+        EulerOrder::YZX,
+        EulerOrder::ZXY,
+        EulerOrder::ZYX,
+        EulerOrder::XYX,
+        EulerOrder::XZX,
+        EulerOrder::YXY,
+        EulerOrder::YZY,
+        EulerOrder::ZXZ,
+        EulerOrder::ZYZ,
+    ];
+
+    /// Indices of the first, second and third rotation axes.
+    pub fn axes(&self) -> [usize; 3] {
+        match self {
+            EulerOrder::XYZ => [0, 1, 2],
+            EulerOrder::XZY => [0, 2, 1],
+            EulerOrder::YXZ => [1, 0, 2],
+            EulerOrder::YZX => [1, 2, 0],
+            EulerOrder::ZXY => [2, 0, 1],
+            EulerOrder::ZYX => [2, 1, 0],
+            EulerOrder::XYX => [0, 1, 0],
+            EulerOrder::XZX => [0, 2, 0],
+            EulerOrder::YXY => [1, 0, 1],
+            EulerOrder::YZY => [1, 2, 1],
+            EulerOrder::ZXZ => [2, 0, 2],
+            EulerOrder::ZYZ => [2, 1, 2],
+        }
+    }
+
+    pub fn is_proper(&self) -> bool {
+        let [i, _, k] = self.axes();
+        i == k
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EulerAngles {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub order: EulerOrder,
+}
+
+fn axis(i: usize) -> Vec3 {
+    match i {
+        0 => Vec3::X,
+        1 => Vec3::Y,
+        _ => Vec3::Z,
+    }
+}
+
+fn from_angle(i: usize, t: f32) -> Mat3 {
+    match i {
+        0 => Mat3::from_angle_x(t),
+        1 => Mat3::from_angle_y(t),
+        _ => Mat3::from_angle_z(t),
+    }
+}
+
+impl EulerAngles {
+    pub fn new(a: f32, b: f32, c: f32, order: EulerOrder) -> Self {
+        Self { a, b, c, order }
+    }
+
+    /// Extracts angles with the middle angle in `[-pi/2, pi/2]` for
+    /// Tait-Bryan orders and `[0, pi]` for proper Euler orders, and the other
+    /// two in `(-pi, pi]`.
+    ///
+    /// In gimbal lock the first and third axes coincide and only their sum
+    /// (or difference) is determined. The third angle is then set to zero
+    /// and the first angle carries the whole rotation.
+    pub fn from_mat3(m: &Mat3, order: EulerOrder) -> Self {
+        // Warning: `m` has to be a rotation
+        // Shoemake, "Euler Angle Conversion", Graphics Gems IV
+        let [i, j, _] = order.axes();
+        let k = 3 - i - j;
+        let odd = (j + 3 - i) % 3 != 1;
+        let threshold = 16. * f32::EPSILON;
+
+        let (a, b, c) = if order.is_proper() {
+            let sy = m[i][j].hypot(m[i][k]);
+            let b = sy.atan2(m[i][i]);
+            if sy > threshold {
+                (m[i][j].atan2(m[i][k]), b, m[j][i].atan2(-m[k][i]))
+            } else {
+                ((-m[j][k]).atan2(m[j][j]), b, 0.)
+            }
+        } else {
+            let cy = m[i][i].hypot(m[j][i]);
+            let b = (-m[k][i]).atan2(cy);
+            if cy > threshold {
+                (m[k][j].atan2(m[k][k]), b, m[j][i].atan2(m[i][i]))
+            } else {
+                ((-m[j][k]).atan2(m[j][j]), b, 0.)
+            }
+        };
+
+        if odd {
+            Self::new(-a, -b, -c, order)
+        } else {
+            Self::new(a, b, c, order)
+        }
+    }
+
+    pub fn from_quat(q: &Quat, order: EulerOrder) -> Self {
+        Self::from_mat3(&Mat3::from(q), order)
+    }
+}
+
+impl_conversions!(EulerAngles => Mat3, |e: &EulerAngles| {
+    let [i, j, k] = e.order.axes();
+    from_angle(k, e.c) * from_angle(j, e.b) * from_angle(i, e.a)
+});
+
+impl_conversions!(EulerAngles => Quat, |e: &EulerAngles| {
+    let [i, j, k] = e.order.axes();
+    Quat::from_axis_angle(axis(k), e.c)
+        * Quat::from_axis_angle(axis(j), e.b)
+        * Quat::from_axis_angle(axis(i), e.a)
+});
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use float_cmp::approx_eq;
+
+    use crate::precision::PRECISION;
+
+    use super::*;
+
+    const SAMPLES: [[f32; 3]; 6] = [
+        [0.3, -0.7, 1.1],
+        [-2.5, 0.2, 0.4],
+        [1.7, 1.3, -3.],
+        [0., 0., 0.],
+        [0.5, 0.01, -0.5],
+        [-0.1, -1.4, 2.9],
+    ];
+
+    #[test]
+    fn composes_extrinsically() {
+        let e = EulerAngles::new(0.3, -0.7, 1.1, EulerOrder::XYZ);
+        assert_eq!(
+            Mat3::from(e),
+            Mat3::from_angle_z(1.1) * Mat3::from_angle_y(-0.7) * Mat3::from_angle_x(0.3)
+        );
+        let v = Vec3::new(1., 2., 3.);
+        let q = Quat::from(e);
+        assert_eq!(
+            q.rotate(&v),
+            Mat3::from_angle_z(1.1) * (Mat3::from_angle_y(-0.7) * (Mat3::from_angle_x(0.3) * v))
+        );
+    }
+
+    #[test]
+    fn matrix_and_quat_agree() {
+        for order in EulerOrder::ALL {
+            for [a, b, c] in SAMPLES {
+                let e = EulerAngles::new(a, b, c, order);
+                assert_eq!(Mat3::from(Quat::from(e)), Mat3::from(e), "{order:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_mat3() {
+        for order in EulerOrder::ALL {
+            for [a, b, c] in SAMPLES {
+                let m = Mat3::from(EulerAngles::new(a, b, c, order));
+                let e = EulerAngles::from_mat3(&m, order);
+                assert_eq!(Mat3::from(e), m, "{order:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_quat() {
+        for order in EulerOrder::ALL {
+            for [a, b, c] in SAMPLES {
+                let q = Quat::from(EulerAngles::new(a, b, c, order));
+                let back = Quat::from(EulerAngles::from_quat(&q, order));
+                assert!(back == q || back == -q, "{order:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn recovers_angles_in_range() {
+        let e = EulerAngles::from_mat3(
+            &Mat3::from(EulerAngles::new(0.3, -0.7, 1.1, EulerOrder::ZXY)),
+            EulerOrder::ZXY,
+        );
+        assert!(approx_eq!(f32, e.a, 0.3, PRECISION));
+        assert!(approx_eq!(f32, e.b, -0.7, PRECISION));
+        assert!(approx_eq!(f32, e.c, 1.1, PRECISION));
+
+        let e = EulerAngles::from_mat3(
+            &Mat3::from(EulerAngles::new(-0.4, 2.5, 1.9, EulerOrder::YZY)),
+            EulerOrder::YZY,
+        );
+        assert!(approx_eq!(f32, e.a, -0.4, PRECISION));
+        assert!(approx_eq!(f32, e.b, 2.5, PRECISION));
+        assert!(approx_eq!(f32, e.c, 1.9, PRECISION));
+    }
+
+    #[test]
+    fn resolves_gimbal_lock() {
+        for order in EulerOrder::ALL {
+            let b = if order.is_proper() { PI } else { FRAC_PI_2 };
+            for b in [b, if order.is_proper() { 0. } else { -b }] {
+                let m = Mat3::from(EulerAngles::new(0.4, b, 0.9, order));
+                let e = EulerAngles::from_mat3(&m, order);
+                assert_eq!(e.c, 0., "{order:?}");
+                assert_eq!(Mat3::from(e), m, "{order:?}");
+            }
+        }
+    }
+}
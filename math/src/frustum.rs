@@ -0,0 +1,187 @@
+use crate::{
+    aabb::Aabb, mat4::Mat4, obb::Obb, plane::Plane, pt3::Pt3, sphere::Sphere,
+    structure::EuclideanSpace, vec4::Vec4,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Intersecting,
+    Outside,
+}
+
+/// View volume bounded by six planes whose normals point inwards, in the
+/// order left, right, top, bottom, near, far. Top is `y = -w` since Vulkan
+/// clip space points y down; near and far are `z = 0` and `z = w`, so they
+/// trade places for reverse-Z projections. The far plane of an infinite
+/// projection has a zero normal and contains everything.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    pub planes: [Plane; 6],
+}
+
+fn plane(v: Vec4) -> Plane {
+    Plane::new(v.x, v.y, v.z, v.w).normalize()
+}
+
+impl Frustum {
+    /// Extracts the planes of the view volume of `view_projection` in world
+    /// space, see Gribb and Hartmann, "Fast Extraction of Viewing Frustum
+    /// Planes from the World-View-Projection Matrix". Clip space is
+    /// `-w <= x, y <= w` and `0 <= z <= w`.
+    pub fn from_matrix(view_projection: &Mat4) -> Self {
+        let Mat4 { a, b, c, d } = *view_projection;
+        Self {
+            planes: [
+                plane(d + a),
+                plane(d - a),
+                plane(d + b),
+                plane(d - b),
+                plane(c),
+                plane(d - c),
+            ],
+        }
+    }
+
+    pub fn contains_point(&self, p: &Pt3) -> bool {
+        self.planes.iter().all(|f| f.dot(p) >= 0.)
+    }
+
+    // Classifies a volume from its center's signed distance to each plane
+    // and its extent along that plane's normal
+    fn classify(&self, center: &Pt3, radius: impl Fn(&Plane) -> f32) -> Containment {
+        let mut result = Containment::Inside;
+        for f in &self.planes {
+            let dist = f.dot(center);
+            let r = radius(f);
+            if dist < -r {
+                return Containment::Outside;
+            }
+            if dist < r {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    pub fn classify_point(&self, p: &Pt3) -> Containment {
+        self.classify(p, |_| 0.)
+    }
+
+    pub fn classify_sphere(&self, s: &Sphere) -> Containment {
+        self.classify(&s.center, |_| s.radius)
+    }
+
+    pub fn classify_aabb(&self, b: &Aabb) -> Containment {
+        let h = b.half_extents();
+        self.classify(&b.center(), |f| {
+            f.x.abs() * h.x + f.y.abs() * h.y + f.z.abs() * h.z
+        })
+    }
+
+    pub fn classify_obb(&self, b: &Obb) -> Containment {
+        let h = b.half_extents;
+        let [x, y, z] = b.axes;
+        self.classify(&b.center, |f| {
+            f.dot(&x).abs() * h.x + f.dot(&y).abs() * h.y + f.dot(&z).abs() * h.z
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use float_cmp::approx_eq;
+
+    use crate::{mat3::Mat3, precision::PRECISION, structure::VecSpace, vec3::Vec3};
+
+    use super::*;
+
+    // Camera at the origin looking down -z, 90 degree field of view, depth
+    // between 1 and 10
+    fn frustum() -> Frustum {
+        let proj = Mat4::perspective_rh(FRAC_PI_2, 1., 1., 10.);
+        let view = Mat4::look_to_rh(Pt3::new(0., 0., 0.), -Vec3::Z, Vec3::Y);
+        Frustum::from_matrix(&(proj * view))
+    }
+
+    #[test]
+    fn extracts_normalized_planes() {
+        let f = frustum();
+        for p in &f.planes {
+            assert!(approx_eq!(f32, p.normal().norm(), 1., PRECISION));
+        }
+        // Near and far planes
+        assert_eq!(f.planes[4].normal(), -Vec3::Z);
+        assert!(approx_eq!(f32, f.planes[4].d, -1., PRECISION));
+        assert_eq!(f.planes[5].normal(), Vec3::Z);
+    }
+
+    #[test]
+    fn classifies_points() {
+        let f = frustum();
+        assert!(f.contains_point(&Pt3::new(0., 0., -5.)));
+        assert!(f.contains_point(&Pt3::new(4.9, -4.9, -5.)));
+        assert!(!f.contains_point(&Pt3::new(5.1, 0., -5.)));
+        assert!(!f.contains_point(&Pt3::new(0., 0., -0.5)));
+        assert!(!f.contains_point(&Pt3::new(0., 0., -11.)));
+        assert!(!f.contains_point(&Pt3::new(0., 0., 5.)));
+        assert_eq!(
+            f.classify_point(&Pt3::new(0., 0., -5.)),
+            Containment::Inside
+        );
+    }
+
+    #[test]
+    fn classifies_spheres() {
+        let f = frustum();
+        let s = |z, r| Sphere::new(Pt3::new(0., 0., z), r);
+        assert_eq!(f.classify_sphere(&s(-5., 1.)), Containment::Inside);
+        assert_eq!(f.classify_sphere(&s(-1., 0.5)), Containment::Intersecting);
+        assert_eq!(f.classify_sphere(&s(-12., 1.)), Containment::Outside);
+        assert_eq!(
+            f.classify_sphere(&Sphere::new(Pt3::new(8., 0., -5.), 1.)),
+            Containment::Outside
+        );
+    }
+
+    #[test]
+    fn classifies_boxes() {
+        let f = frustum();
+        let b = |x, z| Aabb::from_center(Pt3::new(x, 0., z), Vec3::new(1., 1., 1.));
+        assert_eq!(f.classify_aabb(&b(0., -5.)), Containment::Inside);
+        assert_eq!(f.classify_aabb(&b(5., -5.)), Containment::Intersecting);
+        assert_eq!(f.classify_aabb(&b(7., -5.)), Containment::Outside);
+
+        // Turned 45 degrees, the box faces the right plane and reaches less
+        // far across it than its axis-aligned counterpart
+        let r = Mat3::from_angle_y(FRAC_PI_4);
+        let obb = |x| {
+            Obb::new(
+                Pt3::new(x, 0., -5.),
+                [r * Vec3::X, Vec3::Y, r * Vec3::Z],
+                Vec3::new(1., 1., 1.),
+            )
+        };
+        assert_eq!(f.classify_obb(&obb(0.)), Containment::Inside);
+        assert_eq!(f.classify_obb(&obb(6.)), Containment::Intersecting);
+        assert_eq!(f.classify_aabb(&b(6.5, -5.)), Containment::Intersecting);
+        assert_eq!(f.classify_obb(&obb(6.5)), Containment::Outside);
+    }
+
+    #[test]
+    fn handles_reverse_z_and_infinite_projections() {
+        let view = Mat4::look_to_rh(Pt3::new(0., 0., 0.), -Vec3::Z, Vec3::Y);
+        let reverse =
+            Frustum::from_matrix(&(Mat4::perspective_reverse_z_rh(FRAC_PI_2, 1., 1., 10.) * view));
+        assert!(reverse.contains_point(&Pt3::new(0., 0., -5.)));
+        assert!(!reverse.contains_point(&Pt3::new(0., 0., -11.)));
+        assert!(!reverse.contains_point(&Pt3::new(0., 0., -0.5)));
+
+        let infinite =
+            Frustum::from_matrix(&(Mat4::perspective_infinite_rh(FRAC_PI_2, 1., 1.) * view));
+        assert!(infinite.contains_point(&Pt3::new(0., 0., -1e6)));
+        assert!(!infinite.contains_point(&Pt3::new(0., 0., -0.5)));
+    }
+}
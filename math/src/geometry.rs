@@ -1,73 +1,499 @@
+use std::{fmt::Debug, ops::Add};
+
 use crate::{
+    dplane::DPlane,
+    dpt3::DPt3,
+    dvec3::DVec3,
+    grassmann::Antiwedge,
     plane::Plane,
+    precision::is_invertible,
     pt3::Pt3,
-    structure::{EuclideanSpace, VecSpace},
+    scalar::Scalar,
+    structure::{EuclideanSpace, ScalarOf, VecSpace},
     vec3::Vec3,
 };
 
-fn dst_pt_line(q: &Pt3, p: &Pt3, v: &Vec3) -> f32 {
-    let a = Vec3::from(q - p).cross(v);
-    (a.dot(&a) / v.norm2()).sqrt()
-}
-
-fn dst_line_line(p1: &Pt3, v1: &Vec3, p2: &Pt3, v2: &Vec3) -> f32 {
-    let dp = Vec3::from(p2 - p1);
-    let v12 = v1.norm2();
-    let v22 = v2.norm2();
-    let v1v2 = v1.dot(v2);
-    let det = v1v2.powi(2) - v12 * v22;
-    if det.abs() > f32::MIN {
-        // Lines are not parallel
-        let det = 1. / det;
-        let dpv1 = dp.dot(v1);
-        let dpv2 = dp.dot(v2);
-        let t1 = (v1v2 * dpv2 - v22 * dpv1) * det;
-        let t2 = (v12 * dpv2 - v1v2 * dpv1) * det;
-        (dp + v2 * t2 - v1 * t1).norm()
-    } else {
-        // Lines are parallel
-        let a = dp.cross(v1);
-        (a.dot(&a) / v12).sqrt()
-    }
-}
-
-fn intersect_line_plane(p: &Pt3, v: &Vec3, f: &Plane) -> Option<Pt3> {
-    let fv = f.dot(v);
-    if fv.abs() > f32::MIN {
-        let q = p - v * (f.dot(p) / fv);
-        Some(q)
-    } else {
-        None
-    }
-}
-
-fn intersect_three_planes(f1: &Plane, f2: &Plane, f3: &Plane) -> Option<Pt3> {
-    let n1 = f1.normal();
-    let n2 = f2.normal();
-    let n3 = f3.normal();
-    let n1xn2 = n1.cross(&n2);
-    let det = n1xn2.dot(&n3);
-    if det.abs() > f32::MIN {
-        let n3xn2 = n3.cross(&n2);
-        let n1xn3 = n1.cross(&n3);
-        let p = (n3xn2 * f1.d + n1xn3 * f2.d - n1xn2 * f3.d) / det;
-        Some(p.into())
-    } else {
-        None
-    }
-}
-
-fn intersect_two_planes(f1: &Plane, f2: &Plane) -> Option<(Pt3, Vec3)> {
-    let n1 = f1.normal();
-    let n2 = f2.normal();
-    let v = n1.cross(&n2);
-    let det = v.norm2();
-    if det.abs() > f32::MIN {
-        let vxn2 = v.cross(&n2);
-        let n1xv = n1.cross(&v);
-        let p = (vxn2 * f1.d + n1xv * f2.d) / det;
-        Some((p.into(), v))
-    } else {
-        None
+// Directions count as parallel when the squared sine of the angle between
+// them is at most `SINGULAR_EPSILON`, the same scale-free ratio `invert`
+// uses for determinants. Zero-length directions are always parallel.
+
+fn is_parallel<S: Scalar>(sin2_scaled: S, scale: S) -> bool {
+    sin2_scaled.abs() <= S::SINGULAR_EPSILON * scale
+}
+
+/// Point type the lines and their queries are generic over, `Pt3` for
+/// single precision and `DPt3` for double precision.
+pub trait Point
+where
+    Self: VecSpace + Debug,
+    Self: Add<Self::Vector, Output = Self> + Into<Self::Vector>,
+{
+    type Vector: VecSpace + EuclideanSpace<Self::Vector, Scalar = ScalarOf<Self>> + Debug;
+    type Plane: EuclideanSpace<Self, Scalar = ScalarOf<Self>>
+        + EuclideanSpace<Self::Vector, Scalar = ScalarOf<Self>>;
+
+    fn plane_normal(f: &Self::Plane) -> Self::Vector;
+}
+
+impl Point for Pt3 {
+    type Vector = Vec3;
+    type Plane = Plane;
+
+    fn plane_normal(f: &Plane) -> Vec3 {
+        f.normal()
+    }
+}
+
+impl Point for DPt3 {
+    type Vector = DVec3;
+    type Plane = DPlane;
+
+    fn plane_normal(f: &DPlane) -> DVec3 {
+        f.normal()
+    }
+}
+
+type VectorOf<P> = <P as Point>::Vector;
+
+/// Infinite line through `origin` along `dir`, parameterized as
+/// `origin + dir * t` for any `t`.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<P: Point = Pt3> {
+    pub origin: P,
+    pub dir: P::Vector,
+}
+
+/// Half-line `origin + dir * t` for `t >= 0`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ray<P: Point = Pt3> {
+    pub origin: P,
+    pub dir: P::Vector,
+}
+
+/// Segment `a + (b - a) * t` for `0 <= t <= 1`.
+#[derive(Clone, Copy, Debug)]
+pub struct Segment<P: Point = Pt3> {
+    pub a: P,
+    pub b: P,
+}
+
+pub type DLine = Line<DPt3>;
+pub type DRay = Ray<DPt3>;
+pub type DSegment = Segment<DPt3>;
+
+impl<P: Point> Line<P> {
+    pub fn new(origin: P, dir: P::Vector) -> Self {
+        Self { origin, dir }
+    }
+
+    pub fn through(a: P, b: P) -> Self {
+        Self::new(a, (b - a).into())
+    }
+}
+
+impl<P: Point> Ray<P> {
+    pub fn new(origin: P, dir: P::Vector) -> Self {
+        Self { origin, dir }
+    }
+}
+
+impl<P: Point> Segment<P> {
+    pub fn new(a: P, b: P) -> Self {
+        Self { a, b }
+    }
+
+    pub fn length(&self) -> ScalarOf<P> {
+        self.direction().norm()
+    }
+}
+
+/// Shared queries for lines, rays and segments. Every point is
+/// `origin() + direction() * t` with `t` inside `range()`, and the queries
+/// return such parameters so callers can recover points with `at`.
+pub trait Linear {
+    type Point: Point;
+
+    fn origin(&self) -> Self::Point;
+    fn direction(&self) -> VectorOf<Self::Point>;
+    fn range(&self) -> (ScalarOf<Self::Point>, ScalarOf<Self::Point>);
+
+    fn at(&self, t: ScalarOf<Self::Point>) -> Self::Point {
+        self.origin() + self.direction() * t
+    }
+
+    fn clamp(&self, t: ScalarOf<Self::Point>) -> ScalarOf<Self::Point> {
+        let (lo, hi) = self.range();
+        if t < lo {
+            lo
+        } else if t > hi {
+            hi
+        } else {
+            t
+        }
+    }
+
+    /// Parameter of the point closest to `q`. Degenerate directions give
+    /// the origin.
+    fn closest_param(&self, q: &Self::Point) -> ScalarOf<Self::Point> {
+        let d = self.direction();
+        let dd = d.norm2();
+        if dd > Scalar::ZERO {
+            let r: VectorOf<Self::Point> = (*q - self.origin()).into();
+            self.clamp(r.dot(&d) / dd)
+        } else {
+            self.clamp(Scalar::ZERO)
+        }
+    }
+
+    fn closest_point(&self, q: &Self::Point) -> Self::Point {
+        self.at(self.closest_param(q))
+    }
+
+    fn distance_to_point(&self, q: &Self::Point) -> ScalarOf<Self::Point> {
+        let r: VectorOf<Self::Point> = (*q - self.closest_point(q)).into();
+        r.norm()
+    }
+
+    /// Parameters `(s, t)` of the closest pair of points between `self` and
+    /// `other`, see Ericson, RTCD section 5.1.9. For parallel or coincident
+    /// inputs, where the pair is not unique, `s` is the point of `self`
+    /// closest to its origin within range.
+    fn closest_params<L: Linear<Point = Self::Point>>(
+        &self,
+        other: &L,
+    ) -> (ScalarOf<Self::Point>, ScalarOf<Self::Point>) {
+        let zero: ScalarOf<Self::Point> = Scalar::ZERO;
+        let epsilon: ScalarOf<Self::Point> = Scalar::EPSILON;
+        let d1 = self.direction();
+        let d2 = other.direction();
+        let r: VectorOf<Self::Point> = (self.origin() - other.origin()).into();
+        let a = d1.norm2();
+        let e = d2.norm2();
+        let f = d2.dot(&r);
+
+        // A direction much shorter than the other degenerates to a point
+        let point1 = a <= epsilon * e;
+        let point2 = e <= epsilon * a;
+
+        if point1 && point2 {
+            return (self.clamp(zero), other.clamp(zero));
+        }
+        if point1 {
+            let s = self.clamp(zero);
+            return (s, other.closest_param(&self.at(s)));
+        }
+
+        let c = d1.dot(&r);
+        if point2 {
+            return (self.clamp(-c / a), other.clamp(zero));
+        }
+
+        let b = d1.dot(&d2);
+        let denom = a * e - b * b;
+        let s = if is_parallel(denom, a * e) {
+            self.clamp(zero)
+        } else {
+            self.clamp((b * f - c * e) / denom)
+        };
+
+        let t = (b * s + f) / e;
+        let tc = other.clamp(t);
+        if tc != t {
+            (self.clamp((b * tc - c) / a), tc)
+        } else {
+            (s, t)
+        }
+    }
+
+    fn closest_points<L: Linear<Point = Self::Point>>(
+        &self,
+        other: &L,
+    ) -> (Self::Point, Self::Point) {
+        let (s, t) = self.closest_params(other);
+        (self.at(s), other.at(t))
+    }
+
+    fn distance_to<L: Linear<Point = Self::Point>>(&self, other: &L) -> ScalarOf<Self::Point> {
+        let (p, q) = self.closest_points(other);
+        let r: VectorOf<Self::Point> = (q - p).into();
+        r.norm()
+    }
+
+    /// Parameter where the plane is crossed, `None` when parallel to the
+    /// plane or when the crossing is out of range.
+    fn intersect_plane(&self, f: &<Self::Point as Point>::Plane) -> Option<ScalarOf<Self::Point>> {
+        let v = self.direction();
+        let fv = f.dot(&v);
+        let n = Self::Point::plane_normal(f);
+        if is_parallel(fv * fv, n.norm2() * v.norm2()) {
+            return None;
+        }
+        let t = -f.dot(&self.origin()) / fv;
+        let (lo, hi) = self.range();
+        (lo..=hi).contains(&t).then_some(t)
+    }
+}
+
+impl<P: Point> Linear for Line<P> {
+    type Point = P;
+
+    fn origin(&self) -> P {
+        self.origin
+    }
+
+    fn direction(&self) -> P::Vector {
+        self.dir
+    }
+
+    fn range(&self) -> (ScalarOf<P>, ScalarOf<P>) {
+        let inf = <ScalarOf<P> as Scalar>::INFINITY;
+        (-inf, inf)
+    }
+}
+
+impl<P: Point> Linear for Ray<P> {
+    type Point = P;
+
+    fn origin(&self) -> P {
+        self.origin
+    }
+
+    fn direction(&self) -> P::Vector {
+        self.dir
+    }
+
+    fn range(&self) -> (ScalarOf<P>, ScalarOf<P>) {
+        (Scalar::ZERO, Scalar::INFINITY)
+    }
+}
+
+impl<P: Point> Linear for Segment<P> {
+    type Point = P;
+
+    fn origin(&self) -> P {
+        self.a
+    }
+
+    fn direction(&self) -> P::Vector {
+        (self.b - self.a).into()
+    }
+
+    fn range(&self) -> (ScalarOf<P>, ScalarOf<P>) {
+        (Scalar::ZERO, Scalar::ONE)
+    }
+}
+
+impl Plane {
+    /// Common point of three planes, `None` when any two are parallel or
+    /// all three share a line.
+    pub fn intersect_three(f1: &Plane, f2: &Plane, f3: &Plane) -> Option<Pt3> {
+        let p = f1.antiwedge(f2).antiwedge(f3);
+        // The weight is minus the determinant of the three normals
+        let norms = [f1.normal().norm(), f2.normal().norm(), f3.normal().norm()];
+        if is_invertible(p.w, &norms) {
+            Some((p.xyz() / p.w).into())
+        } else {
+            None
+        }
+    }
+
+    /// Line shared by two planes, `None` when they are parallel.
+    pub fn intersect(&self, other: &Plane) -> Option<Line> {
+        let l = self.antiwedge(other);
+        let scale = self.normal().norm2() * other.normal().norm2();
+        if is_parallel(l.dir.norm2(), scale) {
+            return None;
+        }
+        l.to_line()
+    }
+}
+
+impl DPlane {
+    /// Double precision `Plane::intersect_three`, for planes far from the
+    /// origin or nearly parallel to each other.
+    pub fn intersect_three(f1: &DPlane, f2: &DPlane, f3: &DPlane) -> Option<DPt3> {
+        let p = f1.antiwedge(f2).antiwedge(f3);
+        let norms = [f1.normal().norm(), f2.normal().norm(), f3.normal().norm()];
+        if is_invertible(p.w, &norms) {
+            Some((p.xyz() / p.w).into())
+        } else {
+            None
+        }
+    }
+
+    /// Double precision `Plane::intersect`.
+    pub fn intersect(&self, other: &DPlane) -> Option<DLine> {
+        let l = self.antiwedge(other);
+        let scale = self.normal().norm2() * other.normal().norm2();
+        if is_parallel(l.dir.norm2(), scale) {
+            return None;
+        }
+        l.to_line()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use crate::{assert_approx_eq, precision::PRECISION};
+
+    use super::*;
+
+    #[test]
+    fn closest_point_on_line() {
+        let l = Line::new(Pt3::new(1., 0., 0.), Vec3::new(2., 0., 0.));
+        let q = Pt3::new(4., 3., 0.);
+        assert!(approx_eq!(f32, l.closest_param(&q), 1.5, PRECISION));
+        assert_approx_eq!(l.closest_point(&q), Pt3::new(4., 0., 0.));
+        assert!(approx_eq!(f32, l.distance_to_point(&q), 3., PRECISION));
+    }
+
+    #[test]
+    fn clamps_to_ray_and_segment() {
+        let q = Pt3::new(-2., 1., 0.);
+        let r = Ray::new(Pt3::new(0., 0., 0.), Vec3::X);
+        assert_eq!(r.closest_param(&q), 0.);
+        let s = Segment::new(Pt3::new(0., 0., 0.), Pt3::new(1., 0., 0.));
+        assert_eq!(s.closest_param(&Pt3::new(5., 1., 0.)), 1.);
+        assert!(approx_eq!(
+            f32,
+            s.distance_to_point(&q),
+            5_f32.sqrt(),
+            PRECISION
+        ));
+    }
+
+    #[test]
+    fn skew_lines() {
+        let l1 = Line::new(Pt3::new(0., 0., 0.), Vec3::X);
+        let l2 = Line::new(Pt3::new(2., -1., 3.), Vec3::new(0., 2., 0.));
+        let (s, t) = l1.closest_params(&l2);
+        assert!(approx_eq!(f32, s, 2., PRECISION));
+        assert!(approx_eq!(f32, t, 0.5, PRECISION));
+        assert!(approx_eq!(f32, l1.distance_to(&l2), 3., PRECISION));
+    }
+
+    #[test]
+    fn parallel_lines() {
+        let l1 = Line::new(Pt3::new(0., 0., 0.), Vec3::X);
+        let l2 = Line::new(Pt3::new(5., 2., 0.), Vec3::new(-3., 0., 0.));
+        let (s, t) = l1.closest_params(&l2);
+        assert_eq!(s, 0.);
+        assert_approx_eq!(l2.at(t), Pt3::new(0., 2., 0.));
+        assert!(approx_eq!(f32, l1.distance_to(&l2), 2., PRECISION));
+    }
+
+    #[test]
+    fn nearly_parallel_lines_stay_finite() {
+        let l1 = Line::new(Pt3::new(0., 0., 0.), Vec3::X);
+        let l2 = Line::new(Pt3::new(0., 1., 0.), Vec3::new(1., 1e-6, 0.));
+        let (s, t) = l1.closest_params(&l2);
+        assert!(s.is_finite() && t.is_finite());
+        assert!(approx_eq!(f32, l1.distance_to(&l2), 1., PRECISION));
+    }
+
+    #[test]
+    fn coincident_lines() {
+        let l1 = Line::new(Pt3::new(0., 0., 0.), Vec3::X);
+        let l2 = Line::new(Pt3::new(3., 0., 0.), Vec3::X * 2.);
+        let (s, t) = l1.closest_params(&l2);
+        assert_approx_eq!(l1.at(s), l2.at(t));
+        assert_eq!(l1.distance_to(&l2), 0.);
+    }
+
+    #[test]
+    fn segments() {
+        // Closest points of the supporting lines fall outside the segments
+        let s1 = Segment::new(Pt3::new(0., 0., 0.), Pt3::new(1., 0., 0.));
+        let s2 = Segment::new(Pt3::new(3., 1., 0.), Pt3::new(3., 2., 0.));
+        let (s, t) = s1.closest_params(&s2);
+        assert_eq!((s, t), (1., 0.));
+        assert!(approx_eq!(
+            f32,
+            s1.distance_to(&s2),
+            5_f32.sqrt(),
+            PRECISION
+        ));
+
+        // Collinear, overlapping segments
+        let s3 = Segment::new(Pt3::new(0.5, 0., 0.), Pt3::new(2., 0., 0.));
+        assert_eq!(s1.distance_to(&s3), 0.);
+
+        // A degenerate segment acts as a point
+        let p = Segment::new(Pt3::new(0.5, 1., 0.), Pt3::new(0.5, 1., 0.));
+        assert_eq!(s1.closest_params(&p), (0.5, 0.));
+        assert_eq!(p.closest_params(&s1), (0., 0.5));
+    }
+
+    #[test]
+    fn rays_only_look_forward() {
+        let r = Ray::new(Pt3::new(0., 0., 0.), Vec3::X);
+        let l = Line::new(Pt3::new(-2., 0., 1.), Vec3::Y);
+        assert_eq!(r.closest_params(&l), (0., 0.));
+        assert!(approx_eq!(f32, r.distance_to(&l), 5_f32.sqrt(), PRECISION));
+    }
+
+    #[test]
+    fn intersects_plane() {
+        let f = Plane::new(0., 0., 1., -2.);
+        let l = Line::new(Pt3::new(1., 1., 0.), Vec3::new(0., 0., 4.));
+        let t = l.intersect_plane(&f).unwrap();
+        assert_approx_eq!(l.at(t), Pt3::new(1., 1., 2.));
+        assert!(Ray::new(Pt3::new(1., 1., 0.), -Vec3::Z)
+            .intersect_plane(&f)
+            .is_none());
+        assert!(Segment::new(Pt3::new(0., 0., 0.), Pt3::new(0., 0., 1.))
+            .intersect_plane(&f)
+            .is_none());
+        assert!(Line::new(Pt3::new(0., 0., 0.), Vec3::X)
+            .intersect_plane(&f)
+            .is_none());
+    }
+
+    #[test]
+    fn intersects_planes() {
+        let fx = Plane::new(1., 0., 0., -1.);
+        let fy = Plane::new(0., 2., 0., -4.);
+        let fz = Plane::new(0., 0., 1., -3.);
+        let p = Plane::intersect_three(&fx, &fy, &fz).unwrap();
+        assert_approx_eq!(p, Pt3::new(1., 2., 3.));
+        assert!(Plane::intersect_three(&fx, &fy, &Plane::new(1., 0., 0., 5.)).is_none());
+
+        let l = fx.intersect(&fy).unwrap();
+        assert_eq!(l.dir.cross(&Vec3::Z), Vec3::ZERO);
+        assert!(approx_eq!(f32, fx.dot(&l.origin), 0., PRECISION));
+        assert!(approx_eq!(f32, fy.dot(&l.at(7.)), 0., PRECISION));
+        assert!(fx.intersect(&Plane::new(2., 0., 0., 1.)).is_none());
+    }
+
+    #[test]
+    fn double_precision_queries() {
+        // Offsets below the f32 spacing of 1 around 1e7 survive in f64
+        let x = 1e7 + 0.25;
+        let fx = DPlane::new(1., 0., 0., -x);
+        let fy = DPlane::new(0., 1., 0., -2.);
+        let fz = DPlane::new(0., 0., 2., -6.);
+        let p = DPlane::intersect_three(&fx, &fy, &fz).unwrap();
+        assert_eq!(p, DPt3::new(x, 2., 3.));
+        assert!(DPlane::intersect_three(&fx, &fy, &DPlane::new(2., 0., 0., 1.)).is_none());
+
+        let l = fx.intersect(&fy).unwrap();
+        assert_eq!(l.origin, DPt3::new(x, 2., 0.));
+        assert_eq!(l.dir.cross(&DVec3::Z), DVec3::ZERO);
+        assert!(fx.intersect(&DPlane::new(2., 0., 0., 1.)).is_none());
+
+        let s = DSegment::new(DPt3::new(1e7, 0., 0.), DPt3::new(1e7 + 1., 0., 0.));
+        assert_eq!(s.closest_param(&DPt3::new(x, 1., 0.)), 0.25);
+        assert_eq!(s.distance_to_point(&DPt3::new(x, 1., 0.)), 1.);
+
+        let l1 = DLine::new(DPt3::new(1e7, 0., 0.), DVec3::X);
+        let l2 = DLine::new(DPt3::new(1e7 + 0.5, -1., 3.), DVec3::new(0., 2., 0.));
+        assert_eq!(l1.closest_params(&l2), (0.5, 0.5));
+        assert_eq!(l1.distance_to(&l2), 3.);
+
+        let r = DRay::new(DPt3::new(0., 0., 1e7), -DVec3::Z);
+        let floor = DPlane::new(0., 0., 1., -(1e7 - 0.125));
+        assert_eq!(r.intersect_plane(&floor), Some(0.125));
     }
 }
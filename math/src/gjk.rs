@@ -0,0 +1,512 @@
+use crate::{
+    precision::SINGULAR_EPSILON,
+    pt3::Pt3,
+    structure::{EuclideanSpace, VecSpace},
+    support::Support,
+    triangle::Triangle,
+    vec3::Vec3,
+};
+
+const MAX_ITERATIONS: usize = 64;
+
+// Polytopes approximating curved shapes need many more expansions
+const EPA_MAX_ITERATIONS: usize = 128;
+
+/// Relative change of the squared distance below which GJK stops.
+const GJK_TOLERANCE: f32 = 1e-6;
+
+/// Relative change of the penetration depth below which EPA stops.
+const EPA_TOLERANCE: f32 = 1e-4;
+
+/// Closest points of two disjoint shapes, `a` on the first one and `b` on
+/// the second one.
+#[derive(Clone, Copy, Debug)]
+pub struct Separation {
+    pub a: Pt3,
+    pub b: Pt3,
+    pub distance: f32,
+}
+
+/// Overlap of two shapes. Moving the second shape by `normal * depth`
+/// leaves them touching, `a` and `b` are the points of each shape deepest
+/// inside the other one, so `a - b == normal * depth`.
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub a: Pt3,
+    pub b: Pt3,
+    pub normal: Vec3,
+    pub depth: f32,
+}
+
+/// Point `w = a - b` of the Minkowski difference with the points of each
+/// shape it comes from.
+#[derive(Clone, Copy, Debug)]
+struct Vertex {
+    w: Vec3,
+    a: Pt3,
+    b: Pt3,
+}
+
+fn support<A: Support, B: Support>(a: &A, b: &B, dir: &Vec3) -> Vertex {
+    let pa = a.support(dir);
+    let pb = b.support(&-*dir);
+    Vertex {
+        w: Vec3::from(pa - pb),
+        a: pa,
+        b: pb,
+    }
+}
+
+fn independent(x: f32, scale: f32) -> bool {
+    x.abs() > SINGULAR_EPSILON * scale
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Simplex {
+    vertices: [Vertex; 4],
+    weights: [f32; 4],
+    len: usize,
+}
+
+impl Simplex {
+    fn new(v: Vertex) -> Self {
+        Self {
+            vertices: [v; 4],
+            weights: [1., 0., 0., 0.],
+            len: 1,
+        }
+    }
+
+    fn push(&mut self, v: Vertex) {
+        self.vertices[self.len] = v;
+        self.len += 1;
+    }
+
+    fn contains(&self, w: &Vec3) -> bool {
+        self.vertices[..self.len]
+            .iter()
+            .any(|v| v.w.x == w.x && v.w.y == w.y && v.w.z == w.z)
+    }
+
+    fn closest(&self) -> Vec3 {
+        (0..self.len).fold(Vec3::ZERO, |p, i| p + self.vertices[i].w * self.weights[i])
+    }
+
+    fn closest_points(&self) -> (Pt3, Pt3) {
+        let origin = Pt3::new(0., 0., 0.);
+        (0..self.len).fold((origin, origin), |(a, b), i| {
+            let v = &self.vertices[i];
+            (a + v.a * self.weights[i], b + v.b * self.weights[i])
+        })
+    }
+
+    /// Reduces the simplex to the vertices spanning the feature closest to
+    /// the origin and returns the closest point, `None` when the simplex is
+    /// a tetrahedron containing the origin.
+    fn solve(&mut self) -> Option<Vec3> {
+        let w = self.vertices.map(|v| v.w);
+        let weights = match self.len {
+            1 => [1., 0., 0., 0.],
+            2 => {
+                let ab = w[1] - w[0];
+                let len2 = ab.norm2();
+                let t = if len2 > 0. {
+                    (-w[0].dot(&ab) / len2).clamp(0., 1.)
+                } else {
+                    0.
+                };
+                [1. - t, t, 0., 0.]
+            }
+            3 => {
+                let b = triangle_weights(&w[0], &w[1], &w[2]);
+                [b.x, b.y, b.z, 0.]
+            }
+            _ => tetrahedron_weights(&w)?,
+        };
+
+        let mut len = 0;
+        for (i, weight) in weights.into_iter().enumerate().take(self.len) {
+            if weight > 0. {
+                self.vertices[len] = self.vertices[i];
+                self.weights[len] = weight;
+                len += 1;
+            }
+        }
+        self.len = len;
+        Some(self.closest())
+    }
+
+    /// Grows a simplex touching the origin into a tetrahedron enclosing it.
+    /// Fails when the Minkowski difference is flat.
+    fn enclose<A: Support, B: Support>(&mut self, a: &A, b: &B) -> bool {
+        if self.len == 1 {
+            let w0 = self.vertices[0].w;
+            let dirs = [Vec3::X, -Vec3::X, Vec3::Y, -Vec3::Y, Vec3::Z, -Vec3::Z];
+            if let Some(v) = dirs
+                .iter()
+                .map(|d| support(a, b, d))
+                .find(|v| (v.w - w0).norm2() > 0.)
+            {
+                self.push(v);
+            }
+        }
+        if self.len == 2 {
+            let w0 = self.vertices[0].w;
+            let e = self.vertices[1].w - w0;
+            let axis = [Vec3::X, Vec3::Y, Vec3::Z]
+                .into_iter()
+                .min_by(|x, y| e.dot(x).abs().total_cmp(&e.dot(y).abs()))
+                .unwrap();
+            let p = e.cross(&axis);
+            let q = e.cross(&p);
+            if let Some(v) = [p, -p, q, -q].iter().map(|d| support(a, b, d)).find(|v| {
+                let u = v.w - w0;
+                independent(e.cross(&u).norm(), e.norm() * u.norm())
+            }) {
+                self.push(v);
+            }
+        }
+        if self.len == 3 {
+            let w0 = self.vertices[0].w;
+            let n = (self.vertices[1].w - w0).cross(&(self.vertices[2].w - w0));
+            if let Some(v) = [n, -n].iter().map(|d| support(a, b, d)).find(|v| {
+                let u = v.w - w0;
+                independent(n.dot(&u), n.norm() * u.norm())
+            }) {
+                self.push(v);
+            }
+        }
+        self.len == 4
+    }
+}
+
+fn triangle_weights(a: &Vec3, b: &Vec3, c: &Vec3) -> Vec3 {
+    Triangle::new((*a).into(), (*b).into(), (*c).into()).closest_barycentric(&Pt3::new(0., 0., 0.))
+}
+
+// Warning: a degenerate tetrahedron never contains the origin, its faces
+// are all searched instead
+fn tetrahedron_weights(w: &[Vec3; 4]) -> Option<[f32; 4]> {
+    const FACES: [([usize; 3], usize); 4] = [
+        ([0, 1, 2], 3),
+        ([0, 2, 3], 1),
+        ([0, 3, 1], 2),
+        ([1, 3, 2], 0),
+    ];
+
+    let mut best: Option<(f32, [f32; 4])> = None;
+    for ([i, j, k], opposite) in FACES {
+        let n = (w[j] - w[i]).cross(&(w[k] - w[i]));
+        let side_origin = -n.dot(&w[i]);
+        let side_opposite = n.dot(&(w[opposite] - w[i]));
+        if side_origin * side_opposite > 0. || (side_opposite != 0. && side_origin == 0.) {
+            continue;
+        }
+        let b = triangle_weights(&w[i], &w[j], &w[k]);
+        let dist2 = (w[i] * b.x + w[j] * b.y + w[k] * b.z).norm2();
+        if best.is_none_or(|(d, _)| dist2 < d) {
+            let mut weights = [0.; 4];
+            weights[i] = b.x;
+            weights[j] = b.y;
+            weights[k] = b.z;
+            best = Some((dist2, weights));
+        }
+    }
+    best.map(|(_, weights)| weights)
+}
+
+enum Gjk {
+    Separated(Separation),
+    Overlapping(Simplex),
+}
+
+/// Distance between two convex shapes, see van den Bergen, "A Fast and
+/// Robust GJK Implementation for Collision Detection of Convex Objects".
+fn gjk<A: Support, B: Support>(a: &A, b: &B) -> Gjk {
+    let mut simplex = Simplex::new(support(a, b, &Vec3::X));
+    let mut v = simplex.vertices[0].w;
+    for _ in 0..MAX_ITERATIONS {
+        let vv = v.norm2();
+        let scale = simplex.vertices[..simplex.len]
+            .iter()
+            .fold(0f32, |m, s| m.max(s.w.norm2()));
+        if vv <= f32::EPSILON * scale {
+            return Gjk::Overlapping(simplex);
+        }
+
+        let w = support(a, b, &-v);
+        if vv - v.dot(&w.w) <= GJK_TOLERANCE * vv || simplex.contains(&w.w) {
+            break;
+        }
+        simplex.push(w);
+        match simplex.solve() {
+            None => return Gjk::Overlapping(simplex),
+            // Warning: rounding can stall the descent on curved shapes
+            Some(next) if next.norm2() >= vv => break,
+            Some(next) => v = next,
+        }
+    }
+
+    let (pa, pb) = simplex.closest_points();
+    Gjk::Separated(Separation {
+        a: pa,
+        b: pb,
+        distance: (pa - pb).norm(),
+    })
+}
+
+pub(crate) fn intersects<A: Support, B: Support>(a: &A, b: &B) -> bool {
+    matches!(gjk(a, b), Gjk::Overlapping(_))
+}
+
+pub(crate) fn separation<A: Support, B: Support>(a: &A, b: &B) -> Option<Separation> {
+    match gjk(a, b) {
+        Gjk::Separated(s) => Some(s),
+        Gjk::Overlapping(_) => None,
+    }
+}
+
+pub(crate) fn penetration<A: Support, B: Support>(a: &A, b: &B) -> Option<Contact> {
+    match gjk(a, b) {
+        Gjk::Separated(_) => None,
+        Gjk::Overlapping(mut simplex) => {
+            if simplex.enclose(a, b) {
+                Some(epa(a, b, &simplex))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Face {
+    indices: [usize; 3],
+    normal: Vec3,
+    dist: f32,
+}
+
+impl Face {
+    fn new(vertices: &[Vertex], indices: [usize; 3]) -> Self {
+        let [p, q, r] = indices.map(|i| vertices[i].w);
+        let n = (q - p).cross(&(r - p));
+        let len = n.norm();
+        if len > 0. {
+            let normal = n / len;
+            Self {
+                indices,
+                normal,
+                dist: normal.dot(&p),
+            }
+        } else {
+            // Never expanded, the neighbouring faces cover it
+            Self {
+                indices,
+                normal: Vec3::ZERO,
+                dist: f32::INFINITY,
+            }
+        }
+    }
+
+    fn contact(&self, vertices: &[Vertex]) -> Contact {
+        let [p, q, r] = self.indices.map(|i| vertices[i]);
+        let depth = self.dist.max(0.);
+        let b = Triangle::new(p.w.into(), q.w.into(), r.w.into())
+            .barycentric(&(self.normal * depth).into())
+            .unwrap_or(Vec3::X);
+        Contact {
+            a: p.a * b.x + q.a * b.y + r.a * b.z,
+            b: p.b * b.x + q.b * b.y + r.b * b.z,
+            normal: self.normal,
+            depth,
+        }
+    }
+}
+
+/// Penetration of two shapes from a tetrahedron of their Minkowski
+/// difference enclosing the origin with the expanding polytope algorithm.
+fn epa<A: Support, B: Support>(a: &A, b: &B, simplex: &Simplex) -> Contact {
+    let mut vertices = simplex.vertices.to_vec();
+    let [w0, w1, w2, w3] = simplex.vertices.map(|v| v.w);
+    let volume = (w1 - w0).cross(&(w2 - w0)).dot(&(w3 - w0));
+    if volume > 0. {
+        vertices.swap(1, 2);
+    }
+    let mut faces: Vec<Face> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]
+        .into_iter()
+        .map(|f| Face::new(&vertices, f))
+        .collect();
+
+    let closest = |faces: &[Face]| {
+        *faces
+            .iter()
+            .min_by(|f, g| f.dist.total_cmp(&g.dist))
+            .unwrap()
+    };
+    for _ in 0..EPA_MAX_ITERATIONS {
+        let face = closest(&faces);
+        let w = support(a, b, &face.normal);
+        let dist = face.normal.dot(&w.w);
+        if dist - face.dist <= EPA_TOLERANCE * dist.abs() {
+            return face.contact(&vertices);
+        }
+
+        // Replace the faces visible from w by a fan around the boundary
+        // of the hole they leave
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|f| {
+            let visible = f.normal.dot(&(w.w - vertices[f.indices[0]].w)) > 0.;
+            if visible {
+                let [i, j, k] = f.indices;
+                for edge in [(i, j), (j, k), (k, i)] {
+                    match horizon.iter().position(|e| *e == (edge.1, edge.0)) {
+                        Some(shared) => {
+                            horizon.swap_remove(shared);
+                        }
+                        None => horizon.push(edge),
+                    }
+                }
+            }
+            !visible
+        });
+        let apex = vertices.len();
+        vertices.push(w);
+        faces.extend(
+            horizon
+                .iter()
+                .map(|&(i, j)| Face::new(&vertices, [i, j, apex])),
+        );
+    }
+
+    // Warning: deep overlaps of nearly concentric curved shapes can run out
+    // of iterations, leaving the depth slightly underestimated
+    closest(&faces).contact(&vertices)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use float_cmp::approx_eq;
+
+    use crate::{
+        aabb::Aabb,
+        assert_approx_eq,
+        capsule::Capsule,
+        mat3::Mat3,
+        mat4::Mat4,
+        obb::Obb,
+        precision::PRECISION,
+        quat::Quat,
+        sphere::Sphere,
+        support::{ConvexHull, MinkowskiSum, Transformed},
+    };
+
+    use super::*;
+
+    fn unit_box(center: Pt3) -> Aabb {
+        Aabb::from_center(center, Vec3::new(1., 1., 1.))
+    }
+
+    #[test]
+    fn separates_spheres() {
+        let a = Sphere::new(Pt3::new(0., 0., 0.), 1.);
+        let b = Sphere::new(Pt3::new(4., 0., 0.), 1.);
+        assert!(!a.intersects(&b));
+        let s = a.separation(&b).unwrap();
+        assert!(approx_eq!(f32, s.distance, 2., epsilon = 1e-3));
+        assert_approx_eq!(s.a, Pt3::new(1., 0., 0.));
+        assert_approx_eq!(s.b, Pt3::new(3., 0., 0.));
+        assert!(a.penetration(&b).is_none());
+    }
+
+    #[test]
+    fn separates_boxes_and_points() {
+        let a = unit_box(Pt3::new(0., 0., 0.));
+        let b = unit_box(Pt3::new(3., 4., 0.5));
+        let s = a.separation(&b).unwrap();
+        assert!(approx_eq!(f32, s.distance, 5f32.sqrt(), PRECISION));
+        assert_approx_eq!(Pt3::new(s.a.x, s.a.y, 0.), Pt3::new(1., 1., 0.));
+        assert_approx_eq!(Pt3::new(s.b.x, s.b.y, 0.), Pt3::new(2., 3., 0.));
+
+        let p = Pt3::new(0., 5., 0.);
+        let s = p.separation(&a).unwrap();
+        assert!(approx_eq!(f32, s.distance, 4., PRECISION));
+        assert!(Pt3::new(0.5, 0.5, 0.5).intersects(&a));
+    }
+
+    #[test]
+    fn separates_rotated_shapes() {
+        // Turned 45 degrees about z, the box reaches sqrt(2) along x
+        let q = Quat::from_axis_angle(Vec3::Z, FRAC_PI_4);
+        let a =
+            Transformed::from_rotation_translation(unit_box(Pt3::new(0., 0., 0.)), &q, Vec3::ZERO);
+        let c = Capsule::new(Pt3::new(3., -5., 0.), Pt3::new(3., 5., 0.), 0.5);
+        let s = a.separation(&c).unwrap();
+        assert!(approx_eq!(
+            f32,
+            s.distance,
+            2.5 - 2f32.sqrt(),
+            epsilon = 1e-4
+        ));
+        assert_approx_eq!(s.a, Pt3::new(2f32.sqrt(), 0., s.a.z));
+
+        let obb = Obb::from_aabb(&unit_box(Pt3::new(0., 0., 0.)));
+        let obb = obb.transform(&Mat4::from(Mat3::from(q)));
+        assert!(approx_eq!(
+            f32,
+            obb.separation(&c).unwrap().distance,
+            s.distance,
+            epsilon = 1e-4
+        ));
+    }
+
+    #[test]
+    fn measures_penetration() {
+        let a = Sphere::new(Pt3::new(0., 0., 0.), 1.);
+        let b = Sphere::new(Pt3::new(1.5, 0., 0.), 1.);
+        assert!(a.intersects(&b));
+        assert!(a.separation(&b).is_none());
+        let c = a.penetration(&b).unwrap();
+        assert!(approx_eq!(f32, c.depth, 0.5, epsilon = 1e-2));
+        assert!(c.normal.dot(&Vec3::X) > 0.999);
+        assert_approx_eq!(c.a, c.b + c.normal * c.depth);
+
+        let a = unit_box(Pt3::new(0., 0., 0.));
+        let b = unit_box(Pt3::new(0.2, 1.7, -0.1));
+        let c = a.penetration(&b).unwrap();
+        assert!(approx_eq!(f32, c.depth, 0.3, epsilon = 1e-4));
+        assert_eq!(c.normal, Vec3::Y);
+        assert!(approx_eq!(f32, c.a.y, 1., epsilon = 1e-4));
+        assert!(approx_eq!(f32, c.b.y, 0.7, epsilon = 1e-4));
+    }
+
+    #[test]
+    fn handles_touching_and_concentric_shapes() {
+        let a = unit_box(Pt3::new(0., 0., 0.));
+        let c = a.penetration(&a).unwrap();
+        assert!(approx_eq!(f32, c.depth, 2., epsilon = 1e-4));
+
+        let hull = ConvexHull::new(vec![
+            Pt3::new(0., 0., 0.),
+            Pt3::new(1., 0., 0.),
+            Pt3::new(0., 1., 0.),
+            Pt3::new(0., 0., 1.),
+        ])
+        .unwrap();
+        let rounded = MinkowskiSum::new(&hull, Sphere::new(Pt3::new(0., 0., 0.), 0.5));
+        let p = Sphere::new(Pt3::new(0.2, 0.2, -0.6), 0.2);
+        let c = rounded.penetration(&p).unwrap();
+        assert!(approx_eq!(f32, c.depth, 0.1, epsilon = 1e-2));
+        assert!(c.normal.dot(&-Vec3::Z) > 0.99);
+
+        let flat = Triangle::new(
+            Pt3::new(-1., -1., 0.),
+            Pt3::new(1., -1., 0.),
+            Pt3::new(0., 1., 0.),
+        );
+        assert!(flat.intersects(&a));
+        let c = flat.penetration(&a).unwrap();
+        assert!(approx_eq!(f32, c.depth, 1., epsilon = 1e-4));
+    }
+}
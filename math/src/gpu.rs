@@ -0,0 +1,220 @@
+use std::mem::{align_of, size_of};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    affine3::Affine3, dmat3::DMat3, dmat4::DMat4, dplane::DPlane, dpt3::DPt3, dquat::DQuat,
+    dvec3::DVec3, dvec4::DVec4, impl_conversions, mat2::Mat2, mat3::Mat3, mat4::Mat4, plane::Plane,
+    pt2::Pt2, pt3::Pt3, quat::Quat, structure::Mat, vec2::Vec2, vec3::Vec3, vec4::Vec4,
+};
+
+// Every type is `repr(C)` with the `gpu` feature and made of floats only,
+// so none of them has padding. Matrices are stored row by row, shaders
+// reading them directly need `layout(row_major)`.
+macro_rules! impl_pod {
+    ($($A:ty),+) => {
+        $(
+            unsafe impl Zeroable for $A {}
+            unsafe impl Pod for $A {}
+        )+
+    };
+}
+
+impl_pod!(Vec2, Vec3, Vec4, Pt2, Pt3, Quat, Plane, Mat2, Mat3, Mat4, Affine3);
+impl_pod!(DVec3, DVec4, DPt3, DQuat, DPlane, DMat3, DMat4);
+
+/// `vec2` in std140 and std430 blocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C, align(8))]
+pub struct Std140Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// `vec3` in std140 and std430 blocks, aligned like a `vec4`. The trailing
+/// word is padding, a scalar that the shader packs right after a `vec3` has
+/// to be laid out by hand instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C, align(16))]
+pub struct Std140Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    _pad: f32,
+}
+
+/// `vec4` in std140 and std430 blocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C, align(16))]
+pub struct Std140Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Column-major `mat3` in std140 and std430 blocks, each column padded to a
+/// `vec4`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct Std140Mat3 {
+    pub columns: [Std140Vec3; 3],
+}
+
+/// Column-major `mat4` in std140 and std430 blocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct Std140Mat4 {
+    pub columns: [Std140Vec4; 4],
+}
+
+// Vectors and matrices share their layout between the two rules. Arrays of
+// scalars and `vec2` and the alignment of nested structs differ, std140
+// rounds them up to 16 bytes
+pub type Std430Vec2 = Std140Vec2;
+pub type Std430Vec3 = Std140Vec3;
+pub type Std430Vec4 = Std140Vec4;
+pub type Std430Mat3 = Std140Mat3;
+pub type Std430Mat4 = Std140Mat4;
+
+impl_pod!(Std140Vec2, Std140Vec3, Std140Vec4, Std140Mat3, Std140Mat4);
+
+impl_conversions!(Vec2 => Std140Vec2, |v: &Vec2| {
+    Std140Vec2 { x: v.x, y: v.y }
+});
+
+impl_conversions!(Std140Vec2 => Vec2, |v: &Std140Vec2| {
+    Vec2::new(v.x, v.y)
+});
+
+impl_conversions!(Vec3 => Std140Vec3, |v: &Vec3| {
+    Std140Vec3 { x: v.x, y: v.y, z: v.z, _pad: 0. }
+});
+
+impl_conversions!(Std140Vec3 => Vec3, |v: &Std140Vec3| {
+    Vec3::new(v.x, v.y, v.z)
+});
+
+impl_conversions!(Pt3 => Std140Vec3, |p: &Pt3| {
+    Std140Vec3::from(Vec3::from(p))
+});
+
+impl_conversions!(Vec4 => Std140Vec4, |v: &Vec4| {
+    Std140Vec4 { x: v.x, y: v.y, z: v.z, w: v.w }
+});
+
+impl_conversions!(Std140Vec4 => Vec4, |v: &Std140Vec4| {
+    Vec4::new(v.x, v.y, v.z, v.w)
+});
+
+impl_conversions!(Quat => Std140Vec4, |q: &Quat| {
+    Std140Vec4 { x: q.x, y: q.y, z: q.z, w: q.w }
+});
+
+impl_conversions!(Plane => Std140Vec4, |f: &Plane| {
+    Std140Vec4 { x: f.x, y: f.y, z: f.z, w: f.d }
+});
+
+impl_conversions!(Mat3 => Std140Mat3, |m: &Mat3| {
+    let t = m.transpose();
+    Std140Mat3 { columns: [t.a.into(), t.b.into(), t.c.into()] }
+});
+
+impl_conversions!(Std140Mat3 => Mat3, |m: &Std140Mat3| {
+    let [a, b, c] = m.columns.map(Vec3::from);
+    Mat3 { a, b, c }.transpose()
+});
+
+impl_conversions!(Mat4 => Std140Mat4, |m: &Mat4| {
+    let t = m.transpose();
+    Std140Mat4 { columns: [t.a.into(), t.b.into(), t.c.into(), t.d.into()] }
+});
+
+impl_conversions!(Std140Mat4 => Mat4, |m: &Std140Mat4| {
+    let [a, b, c, d] = m.columns.map(Vec4::from);
+    Mat4 { a, b, c, d }.transpose()
+});
+
+impl_conversions!(Affine3 => Std140Mat4, |m: &Affine3| {
+    Std140Mat4::from(Mat4::from(m))
+});
+
+macro_rules! assert_layout {
+    ($($A:ty => $size:expr, $align:expr);+ $(;)?) => {
+        $(
+            const _: () = assert!(size_of::<$A>() == $size && align_of::<$A>() == $align);
+        )+
+    };
+}
+
+// Vec4 and Quat are over-aligned by the `simd` feature
+const LANE_ALIGN: usize = if cfg!(feature = "simd") { 16 } else { 4 };
+
+assert_layout!(
+    Vec2 => 8, 4;
+    Vec3 => 12, 4;
+    Vec4 => 16, LANE_ALIGN;
+    Pt2 => 8, 4;
+    Pt3 => 12, 4;
+    Quat => 16, LANE_ALIGN;
+    Plane => 16, 4;
+    Mat2 => 16, 4;
+    Mat3 => 36, 4;
+    Mat4 => 64, LANE_ALIGN;
+    Affine3 => 48, LANE_ALIGN;
+    DVec3 => 24, 8;
+    DVec4 => 32, 8;
+    DPt3 => 24, 8;
+    DQuat => 32, 8;
+    DPlane => 32, 8;
+    DMat3 => 72, 8;
+    DMat4 => 128, 8;
+    Std140Vec2 => 8, 8;
+    Std140Vec3 => 16, 16;
+    Std140Vec4 => 16, 16;
+    Std140Mat3 => 48, 16;
+    Std140Mat4 => 64, 16;
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::structure::SquareMat;
+
+    use super::*;
+
+    #[test]
+    fn casts_to_bytes() {
+        let v = Vec3::new(1., 2., 3.);
+        let floats: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&v));
+        assert_eq!(floats, &[1., 2., 3.]);
+
+        let points = [Pt3::new(1., 2., 3.), Pt3::new(4., 5., 6.)];
+        assert_eq!(bytemuck::cast_slice::<Pt3, u8>(&points).len(), 24);
+        assert_eq!(bytemuck::cast::<[f32; 4], Plane>([0., 1., 0., 2.]).d, 2.);
+    }
+
+    #[test]
+    fn pads_vec3() {
+        let v = Std140Vec3::from(Vec3::new(1., 2., 3.));
+        let floats: [f32; 4] = bytemuck::cast(v);
+        assert_eq!(floats, [1., 2., 3., 0.]);
+        assert_eq!(Vec3::from(v), Vec3::new(1., 2., 3.));
+    }
+
+    #[test]
+    fn stores_matrices_by_column() {
+        let mut m = Mat4::IDENTITY;
+        m.a.w = 5.;
+        let gpu = Std140Mat4::from(m);
+        let floats: [f32; 16] = bytemuck::cast(gpu);
+        // The translation lands in the last column, as GLSL expects
+        assert_eq!(floats[12], 5.);
+        assert_eq!(Mat4::from(gpu), m);
+
+        let r = Mat3::from_angle_z(0.5);
+        let gpu = Std140Mat3::from(r);
+        let floats: [f32; 12] = bytemuck::cast(gpu);
+        assert_eq!(&floats[..4], &[r.a.x, r.b.x, r.c.x, 0.]);
+        assert_eq!(Mat3::from(gpu), r);
+    }
+}
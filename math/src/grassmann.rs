@@ -0,0 +1,446 @@
+use std::ops;
+
+use crate::{
+    dplane::DPlane,
+    dvec3::DVec3,
+    dvec4::DVec4,
+    geometry::{DLine, Line},
+    impl_op,
+    mat4::Mat4,
+    plane::Plane,
+    pt3::Pt3,
+    structure::{EuclideanSpace, Mat, VecSpace},
+    vec3::Vec3,
+    vec4::Vec4,
+};
+
+// Grassmann algebra after Lengyel, "Foundations of Game Engine Development,
+// Volume 1", chapter 4. In 4D projective space points are vectors, lines
+// bivectors and planes trivectors; `Plane` already plays the trivector part.
+// The wedge product joins and the antiwedge product meets.
+
+/// Exterior product, which joins its operands into the smallest object
+/// containing both.
+pub trait Wedge<Rhs = Self> {
+    type Output;
+    fn wedge(&self, rhs: &Rhs) -> Self::Output;
+}
+
+/// Exterior antiproduct, which meets its operands in the largest object
+/// both contain.
+pub trait Antiwedge<Rhs = Self> {
+    type Output;
+    fn antiwedge(&self, rhs: &Rhs) -> Self::Output;
+}
+
+/// Oriented area in 3D with components on `e23`, `e31` and `e12`, such as
+/// `a ∧ b` for two vectors. Its complement is the cross product.
+#[derive(Clone, Copy, Debug)]
+pub struct Bivector3 {
+    pub yz: f32,
+    pub zx: f32,
+    pub xy: f32,
+}
+
+/// Oriented volume in 3D, a multiple of `e123`.
+#[derive(Clone, Copy, Debug)]
+pub struct Trivector {
+    pub xyz: f32,
+}
+
+/// Line in Plücker coordinates `{dir | moment}`. Through a point `p` the
+/// moment is `p × dir`, the line at infinity has a zero `dir` and scaling
+/// both parts gives the same line.
+#[derive(Clone, Copy, Debug)]
+pub struct PluckerLine {
+    pub dir: Vec3,
+    pub moment: Vec3,
+}
+
+/// Double precision counterpart of `PluckerLine`, the meet of two
+/// `DPlane`s.
+#[derive(Clone, Copy, Debug)]
+pub struct DPluckerLine {
+    pub dir: DVec3,
+    pub moment: DVec3,
+}
+
+impl Bivector3 {
+    pub fn new(yz: f32, zx: f32, xy: f32) -> Self {
+        Self { yz, zx, xy }
+    }
+
+    /// Vector perpendicular to the area with the same magnitude.
+    pub fn complement(&self) -> Vec3 {
+        Vec3::new(self.yz, self.zx, self.xy)
+    }
+}
+
+impl Trivector {
+    pub fn new(xyz: f32) -> Self {
+        Self { xyz }
+    }
+}
+
+impl PluckerLine {
+    pub fn new(dir: Vec3, moment: Vec3) -> Self {
+        Self { dir, moment }
+    }
+
+    /// Same line with a unit direction, so the moment's length is the
+    /// line's distance from the origin. Lines at infinity are returned
+    /// unchanged.
+    pub fn normalize(&self) -> Self {
+        let n = self.dir.norm();
+        if n > 0. {
+            Self::new(self.dir / n, self.moment / n)
+        } else {
+            *self
+        }
+    }
+
+    /// Point-direction form, `None` for a line at infinity.
+    pub fn to_line(&self) -> Option<Line> {
+        let v2 = self.dir.norm2();
+        if v2 > 0. {
+            // Point of the line closest to the origin
+            let p = self.dir.cross(&self.moment) / v2;
+            Some(Line::new(p.into(), self.dir))
+        } else {
+            None
+        }
+    }
+
+    /// Image of the line under the projective map `m`, which is the join of
+    /// the images of any two of its points. Lines sent to infinity come out
+    /// with a zero `dir`.
+    pub fn transform(&self, m: &Mat4) -> Self {
+        // The line is `dir.x (w ∧ x) + moment.x (y ∧ z)` and so on over the
+        // basis points, whose images are the columns of `m`
+        let t = m.transpose();
+        let (x, y, z, w) = (t.a, t.b, t.c, t.d);
+        [
+            (w.wedge(&x), self.dir.x),
+            (w.wedge(&y), self.dir.y),
+            (w.wedge(&z), self.dir.z),
+            (y.wedge(&z), self.moment.x),
+            (z.wedge(&x), self.moment.y),
+            (x.wedge(&y), self.moment.z),
+        ]
+        .iter()
+        .fold(Self::new(Vec3::ZERO, Vec3::ZERO), |acc, (l, k)| {
+            Self::new(acc.dir + l.dir * *k, acc.moment + l.moment * *k)
+        })
+    }
+}
+
+impl DPluckerLine {
+    pub fn new(dir: DVec3, moment: DVec3) -> Self {
+        Self { dir, moment }
+    }
+
+    /// Point-direction form, `None` for a line at infinity.
+    pub fn to_line(&self) -> Option<DLine> {
+        let v2 = self.dir.norm2();
+        if v2 > 0. {
+            let p = self.dir.cross(&self.moment) / v2;
+            Some(DLine::new(p.into(), self.dir))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Line> for PluckerLine {
+    fn from(l: Line) -> Self {
+        Self::new(l.dir, Vec3::from(l.origin).cross(&l.dir))
+    }
+}
+
+impl_op!(PluckerLine, ops::Neg { fn neg |l: &PluckerLine| {
+    PluckerLine::new(-l.dir, -l.moment)
+}});
+
+fn point(p: &Pt3) -> Vec4 {
+    Vec3::from(p).extend(1.)
+}
+
+impl Wedge for Vec3 {
+    type Output = Bivector3;
+
+    fn wedge(&self, b: &Vec3) -> Bivector3 {
+        let c = self.cross(b);
+        Bivector3::new(c.x, c.y, c.z)
+    }
+}
+
+impl Wedge<Vec3> for Bivector3 {
+    type Output = Trivector;
+
+    fn wedge(&self, v: &Vec3) -> Trivector {
+        Trivector::new(self.complement().dot(v))
+    }
+}
+
+impl Wedge<Bivector3> for Vec3 {
+    type Output = Trivector;
+
+    fn wedge(&self, b: &Bivector3) -> Trivector {
+        b.wedge(self)
+    }
+}
+
+impl Antiwedge for Bivector3 {
+    type Output = Vec3;
+
+    /// Direction shared by two planes through the origin.
+    fn antiwedge(&self, b: &Bivector3) -> Vec3 {
+        self.complement().cross(&b.complement())
+    }
+}
+
+impl Antiwedge<Vec3> for Bivector3 {
+    type Output = f32;
+
+    fn antiwedge(&self, v: &Vec3) -> f32 {
+        self.complement().dot(v)
+    }
+}
+
+impl Wedge for Vec4 {
+    type Output = PluckerLine;
+
+    /// Line through two homogeneous points.
+    fn wedge(&self, q: &Vec4) -> PluckerLine {
+        let (p3, q3) = (self.xyz(), q.xyz());
+        PluckerLine::new(q3 * self.w - p3 * q.w, p3.cross(&q3))
+    }
+}
+
+impl Wedge for Pt3 {
+    type Output = PluckerLine;
+
+    /// Line from `self` toward `q`.
+    fn wedge(&self, q: &Pt3) -> PluckerLine {
+        point(self).wedge(&point(q))
+    }
+}
+
+impl Wedge<Vec4> for PluckerLine {
+    type Output = Plane;
+
+    /// Plane containing the line and a homogeneous point, with a zero
+    /// normal when the point lies on the line.
+    fn wedge(&self, p: &Vec4) -> Plane {
+        let n = self.dir.cross(&p.xyz()) + self.moment * p.w;
+        Plane::new(n.x, n.y, n.z, -p.xyz().dot(&self.moment))
+    }
+}
+
+impl Wedge<Pt3> for PluckerLine {
+    type Output = Plane;
+
+    fn wedge(&self, p: &Pt3) -> Plane {
+        self.wedge(&point(p))
+    }
+}
+
+impl Wedge<PluckerLine> for Pt3 {
+    type Output = Plane;
+
+    fn wedge(&self, l: &PluckerLine) -> Plane {
+        l.wedge(self)
+    }
+}
+
+impl Antiwedge for Plane {
+    type Output = PluckerLine;
+
+    /// Line shared by two planes, at infinity when they are parallel.
+    fn antiwedge(&self, g: &Plane) -> PluckerLine {
+        let (n, m) = (self.normal(), g.normal());
+        PluckerLine::new(n.cross(&m), m * self.d - n * g.d)
+    }
+}
+
+impl Antiwedge<Plane> for PluckerLine {
+    type Output = Vec4;
+
+    /// Homogeneous point where the line crosses the plane, with `w = 0`
+    /// when they are parallel.
+    fn antiwedge(&self, f: &Plane) -> Vec4 {
+        let n = f.normal();
+        (self.moment.cross(&n) + self.dir * f.d).extend(-n.dot(&self.dir))
+    }
+}
+
+impl Antiwedge for DPlane {
+    type Output = DPluckerLine;
+
+    fn antiwedge(&self, g: &DPlane) -> DPluckerLine {
+        let (n, m) = (self.normal(), g.normal());
+        DPluckerLine::new(n.cross(&m), m * self.d - n * g.d)
+    }
+}
+
+impl Antiwedge<DPlane> for DPluckerLine {
+    type Output = DVec4;
+
+    fn antiwedge(&self, f: &DPlane) -> DVec4 {
+        let n = f.normal();
+        (self.moment.cross(&n) + self.dir * f.d).extend(-n.dot(&self.dir))
+    }
+}
+
+impl Antiwedge<PluckerLine> for Plane {
+    type Output = Vec4;
+
+    fn antiwedge(&self, l: &PluckerLine) -> Vec4 {
+        l.antiwedge(self)
+    }
+}
+
+impl Antiwedge for PluckerLine {
+    type Output = f32;
+
+    /// Zero exactly when the lines are coplanar, otherwise its sign tells
+    /// how one winds around the other.
+    fn antiwedge(&self, l: &PluckerLine) -> f32 {
+        -(self.dir.dot(&l.moment) + l.dir.dot(&self.moment))
+    }
+}
+
+impl Antiwedge<Vec4> for Plane {
+    type Output = f32;
+
+    fn antiwedge(&self, p: &Vec4) -> f32 {
+        self.normal().dot(&p.xyz()) + self.d * p.w
+    }
+}
+
+impl Antiwedge<Pt3> for Plane {
+    type Output = f32;
+
+    fn antiwedge(&self, p: &Pt3) -> f32 {
+        self.dot(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::{
+        approx::ApproxEq,
+        assert_approx_eq,
+        mat3::Mat3,
+        structure::SquareMat,
+        transform4::{T4Mul, Transform4},
+    };
+
+    use super::*;
+
+    #[test]
+    fn wedges_vectors() {
+        let a = Vec3::new(1., 2., 0.);
+        let b = Vec3::new(0., 1., 3.);
+        let c = Vec3::new(2., 0., 1.);
+        let ab = a.wedge(&b);
+        assert_eq!(ab.complement(), a.cross(&b));
+        assert!((ab.wedge(&c).xyz - a.cross(&b).dot(&c)).abs() < 1e-6);
+        assert_eq!(ab.wedge(&c).xyz, c.wedge(&ab).xyz);
+        // Two planes through the origin share a line
+        let shared = ab.antiwedge(&b.wedge(&c));
+        assert!(shared.dot(&ab.complement()).abs() < 1e-5);
+        assert_eq!(ab.antiwedge(&a), 0.);
+    }
+
+    #[test]
+    fn joins_points() {
+        let p = Pt3::new(1., 0., 0.);
+        let q = Pt3::new(1., 3., 0.);
+        let l = p.wedge(&q);
+        assert_eq!(l.dir, Vec3::new(0., 3., 0.));
+        assert_eq!(l.moment, Vec3::new(0., 0., 3.));
+        let line = l.to_line().unwrap();
+        assert_approx_eq!(line.origin, p);
+        assert_approx_eq!(PluckerLine::from(line).normalize(), l.normalize());
+
+        // The plane x = 1 through the line and (1, 0, 5)
+        let f = l.wedge(&Pt3::new(1., 0., 5.)).normalize();
+        assert_approx_eq!(f, Plane::new(1., 0., 0., -1.));
+        let on = l.wedge(&Pt3::new(1., 7., 0.));
+        assert_approx_eq!(on.normal(), Vec3::ZERO);
+
+        // A point at infinity gives a line through p along its direction
+        let toward = point(&p).wedge(&Vec3::Z.extend(0.));
+        assert_approx_eq!(toward.normalize(), PluckerLine::from(Line::new(p, Vec3::Z)));
+    }
+
+    #[test]
+    fn meets_planes() {
+        let fx = Plane::new(1., 0., 0., -1.);
+        let fy = Plane::new(0., 1., 0., -2.);
+        let fz = Plane::new(0., 0., 1., -3.);
+        let l = fz.antiwedge(&fx);
+        let line = l.to_line().unwrap();
+        assert!(fx.dot(&line.origin).abs() < 1e-6);
+        assert!(fz.dot(&(line.origin + line.dir * 4.)).abs() < 1e-6);
+
+        let p = l.antiwedge(&fy);
+        assert_approx_eq!(p.xyz() / p.w, Vec3::new(1., 2., 3.));
+        assert_approx_eq!(fy.antiwedge(&l), p);
+        assert_eq!(fy.antiwedge(&p), 0.);
+        assert_eq!(l.antiwedge(&Plane::new(0., 0., 2., 1.)).w, 0.);
+        assert_eq!(fx.antiwedge(&Plane::new(2., 0., 0., 1.)).dir, Vec3::ZERO);
+
+        let (dx, dy, dz) = (DPlane::from(fx), DPlane::from(fy), DPlane::from(fz));
+        let p = dz.antiwedge(&dx).antiwedge(&dy);
+        assert_eq!(p.xyz() / p.w, DVec3::new(1., 2., 3.));
+        assert!(dz.antiwedge(&dx).to_line().is_some());
+        assert!(dx
+            .antiwedge(&DPlane::new(2., 0., 0., 1.))
+            .to_line()
+            .is_none());
+    }
+
+    #[test]
+    fn classifies_line_pairs() {
+        let a = Pt3::new(0., 0., 0.).wedge(&Pt3::new(1., 0., 0.));
+        let crossing = Pt3::new(0., -1., 0.).wedge(&Pt3::new(0., 1., 0.));
+        let above = Pt3::new(0., -1., 1.).wedge(&Pt3::new(0., 1., 1.));
+        assert_eq!(a.antiwedge(&crossing), 0.);
+        assert!(a.antiwedge(&above).abs() > 0.);
+        assert_eq!(a.antiwedge(&above), above.antiwedge(&a));
+        assert_eq!((-above).antiwedge(&a), -above.antiwedge(&a));
+    }
+
+    #[test]
+    fn transforms_lines() {
+        let mut m =
+            Mat4::from(Mat3::from_angle_z(FRAC_PI_2) * Mat3::from_diagonal(Vec3::new(2., 1., 3.)));
+        m.set_translation(Vec3::new(1., -2., 5.));
+        let p = Pt3::new(1., 2., 3.);
+        let q = Pt3::new(-1., 0., 4.);
+        let l = p.wedge(&q).transform(&m);
+        let image = T4Mul::mul(&m, &p).wedge(&T4Mul::mul(&m, &q));
+        assert!(l.abs_diff_eq(&image, 1e-4));
+    }
+
+    #[test]
+    fn transforms_lines_projectively() {
+        // The bottom row of a perspective matrix counts, the images of the
+        // points are homogeneous
+        let m = Mat4::perspective_rh(1., 1.5, 0.1, 10.);
+        let (p, q) = (
+            point(&Pt3::new(1., 2., -3.)),
+            point(&Pt3::new(-1., 0., -4.)),
+        );
+        let l = p.wedge(&q).transform(&m);
+        assert!(l.abs_diff_eq(&(m * p).wedge(&(m * q)), 1e-4));
+
+        // A line through the eye parallel to the near plane goes to infinity
+        let through_eye = Pt3::new(0., 0., 0.).wedge(&Pt3::new(1., 0., 0.));
+        assert_eq!(through_eye.transform(&m).dir, Vec3::ZERO);
+    }
+}
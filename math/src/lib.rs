@@ -1,24 +1,101 @@
+mod aabb;
+mod affine3;
+mod approx;
+mod bvh;
+mod capsule;
+mod dmat3;
+mod dmat4;
+mod dplane;
+mod dpt3;
+mod dquat;
+mod dual_quat;
+mod dvec3;
+mod dvec4;
+mod euler;
+mod frustum;
 mod geometry;
+mod gjk;
+#[cfg(feature = "gpu")]
+mod gpu;
+mod grassmann;
 mod macros;
 mod mat;
+mod mat2;
 mod mat3;
 mod mat4;
+mod obb;
 mod plane;
 mod precision;
+mod projection;
 mod pt;
+mod pt2;
 mod pt3;
 mod quat;
+mod raycast;
+mod sat;
+mod scalar;
+#[cfg(feature = "serde")]
+mod serialize;
+mod simd;
+mod sphere;
 mod structure;
+mod support;
+mod swizzle;
+mod transform;
+mod transform3;
 mod transform4;
+mod triangle;
 mod vec;
+mod vec2;
 mod vec3;
 mod vec4;
+mod view;
 
+pub use aabb::Aabb;
+pub use affine3::{Affine3, NotAffine};
+#[doc(hidden)]
+pub use approx::{assert_abs, assert_rel};
+pub use approx::{ApproxEq, Exact};
+pub use bvh::{Bounded, Bvh, ClosestPoint};
+pub use capsule::Capsule;
+pub use dmat3::DMat3;
+pub use dmat4::DMat4;
+pub use dplane::DPlane;
+pub use dpt3::DPt3;
+pub use dquat::DQuat;
+pub use dual_quat::DualQuat;
+pub use dvec3::DVec3;
+pub use dvec4::DVec4;
+pub use euler::{EulerAngles, EulerOrder};
+pub use frustum::{Containment, Frustum};
+pub use geometry::{DLine, DRay, DSegment, Line, Linear, Point, Ray, Segment};
+pub use gjk::{Contact, Separation};
+#[cfg(feature = "gpu")]
+pub use gpu::{
+    Std140Mat3, Std140Mat4, Std140Vec2, Std140Vec3, Std140Vec4, Std430Mat3, Std430Mat4, Std430Vec2,
+    Std430Vec3, Std430Vec4,
+};
+pub use grassmann::{Antiwedge, Bivector3, DPluckerLine, PluckerLine, Trivector, Wedge};
+pub use mat2::Mat2;
 pub use mat3::Mat3;
 pub use mat4::Mat4;
-pub use plane::Plane;
+pub use obb::Obb;
+pub use plane::{Plane, Side};
+pub use precision::{PRECISION, PRECISION_F64, SINGULAR_EPSILON};
+pub use pt2::Pt2;
 pub use pt3::Pt3;
 pub use quat::Quat;
-pub use transform4::Transform4;
+pub use raycast::{RayCast, RayHit};
+pub use sat::{Overlap, SeparatingAxis};
+pub use scalar::Scalar;
+pub use sphere::Sphere;
+pub use structure::{EuclideanSpace, Mat, ProjectionSpace, ScalarOf, SquareMat, VecSpace};
+pub use support::{ConvexHull, MinkowskiSum, Support, Transformed};
+pub use transform::Transform;
+pub use transform3::{T3Mul, Transform3};
+pub use transform4::{T4Mul, Transform4};
+pub use triangle::Triangle;
+pub use vec2::Vec2;
 pub use vec3::Vec3;
 pub use vec4::Vec4;
+pub use view::CameraBasis;
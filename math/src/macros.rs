@@ -172,38 +172,41 @@ macro_rules! impl_index_ops {
 
 #[macro_export]
 macro_rules! impl_algebraic_ops {
-    ($Lhs:ident { $($field:ident),+ }, $n:expr) => {
+    ($Lhs:ident { $($field:ident),+ }, $n:expr, $S:ty) => {
         $crate::impl_op!($Lhs, ops::Neg { fn neg |lhs: &$Lhs| {
             *lhs * -1.
         }});
 
         $crate::impl_op!(
-            $Lhs : f32, ops::Mul { fn mul |lhs: &$Lhs, rhs: &f32| {
+            $Lhs : $S, ops::Mul { fn mul |lhs: &$Lhs, rhs: &$S| {
                 $Lhs { $($field: lhs.$field * rhs),+ }
             }}
         );
 
         $crate::impl_op_assign!(
-            $Lhs, f32, ops::MulAssign { fn mul_assign |lhs: &mut $Lhs, rhs: &f32| {
+            $Lhs, $S, ops::MulAssign { fn mul_assign |lhs: &mut $Lhs, rhs: &$S| {
                 *lhs = *lhs * *rhs;
             }}
         );
 
         $crate::impl_op!(
-            $Lhs : f32, ops::Div { fn div |lhs: &$Lhs, rhs: &f32| {
+            $Lhs : $S, ops::Div { fn div |lhs: &$Lhs, rhs: &$S| {
                 let inv = 1. / rhs;
                 $Lhs { $($field: lhs.$field * inv),+ }
             }}
         );
 
         $crate::impl_op_assign!(
-            $Lhs, f32, ops::DivAssign { fn div_assign |lhs: &mut $Lhs, rhs: &f32| {
+            $Lhs, $S, ops::DivAssign { fn div_assign |lhs: &mut $Lhs, rhs: &$S| {
                 *lhs = *lhs / *rhs;
             }}
         );
 
         $crate::impl_algebraic_ops!($Lhs : $Lhs { $($field),+ }, $n);
     };
+    ($Lhs:ident { $($field:ident),+ }, $n:expr) => {
+        $crate::impl_algebraic_ops!($Lhs { $($field),+ }, $n, f32);
+    };
     ($Lhs:ident : $Rhs:ident { $($field:ident),+ }, $n:expr) => {
         $crate::impl_op!($Lhs : $Rhs, ops::Add { fn add |lhs: &$Lhs, rhs: &$Rhs| {
             $Lhs { $($field: lhs.$field + rhs.$field),+ }
@@ -226,3 +229,123 @@ macro_rules! impl_algebraic_ops {
         );
     };
 }
+
+#[macro_export]
+macro_rules! impl_componentwise_ops {
+    ($T:ident { $($field:ident),+ }, $n:expr, $S:ty) => {
+        $crate::impl_op!($T : $T, ops::Mul { fn mul |lhs: &$T, rhs: &$T| {
+            $T { $($field: lhs.$field * rhs.$field),+ }
+        }});
+
+        $crate::impl_op_assign!($T, $T, ops::MulAssign { fn mul_assign |lhs: &mut $T, rhs: &$T| {
+            *lhs = *lhs * rhs;
+        }});
+
+        $crate::impl_op!($T : $T, ops::Div { fn div |lhs: &$T, rhs: &$T| {
+            $T { $($field: lhs.$field / rhs.$field),+ }
+        }});
+
+        $crate::impl_op_assign!($T, $T, ops::DivAssign { fn div_assign |lhs: &mut $T, rhs: &$T| {
+            *lhs = *lhs / rhs;
+        }});
+
+        impl $T {
+            pub fn min(&self, other: &Self) -> Self {
+                Self { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            pub fn max(&self, other: &Self) -> Self {
+                Self { $($field: self.$field.max(other.$field)),+ }
+            }
+
+            pub fn clamp(&self, min: &Self, max: &Self) -> Self {
+                self.max(min).min(max)
+            }
+
+            pub fn abs(&self) -> Self {
+                Self { $($field: self.$field.abs()),+ }
+            }
+
+            pub fn floor(&self) -> Self {
+                Self { $($field: self.$field.floor()),+ }
+            }
+
+            pub fn ceil(&self) -> Self {
+                Self { $($field: self.$field.ceil()),+ }
+            }
+
+            pub fn round(&self) -> Self {
+                Self { $($field: self.$field.round()),+ }
+            }
+
+            pub fn lerp(&self, other: &Self, t: $S) -> Self {
+                Self { $($field: self.$field + (other.$field - self.$field) * t),+ }
+            }
+
+            pub fn min_element(&self) -> $S {
+                [$(self.$field),+].into_iter().fold(<$S>::INFINITY, <$S>::min)
+            }
+
+            pub fn max_element(&self) -> $S {
+                [$(self.$field),+].into_iter().fold(<$S>::NEG_INFINITY, <$S>::max)
+            }
+
+            /// Takes each component from `if_true` where `mask` is set and
+            /// from `if_false` elsewhere.
+            pub fn select(mask: [bool; $n], if_true: &Self, if_false: &Self) -> Self {
+                let [$($field),+] = mask;
+                Self { $($field: if $field { if_true.$field } else { if_false.$field }),+ }
+            }
+        }
+    };
+    ($T:ident { $($field:ident),+ }, $n:expr) => {
+        $crate::impl_componentwise_ops!($T { $($field),+ }, $n, f32);
+    };
+}
+
+/// Generates swizzle accessors from a table of `name(components..)`
+/// entries, returning a `$Out` built with `$Out::new`.
+#[macro_export]
+macro_rules! impl_swizzles {
+    ($T:ident => $Out:ident { $($name:ident($($field:ident),+))+ }) => {
+        impl $T {
+            $(
+                pub fn $name(&self) -> $Out {
+                    $Out::new($(self.$field),+)
+                }
+            )+
+        }
+    };
+}
+
+/// Asserts that two math values are component-wise within an absolute
+/// margin, `epsilon = ..` (`Scalar::DEFAULT_EPSILON` by default) and/or
+/// `ulps = ..`. The panic message lists the components that differ.
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_abs(&$left, &$right, None, None)
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr $(,)?) => {
+        $crate::assert_abs(&$left, &$right, Some($epsilon), None)
+    };
+    ($left:expr, $right:expr, ulps = $ulps:expr $(,)?) => {
+        $crate::assert_abs(&$left, &$right, None, Some($ulps))
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr, ulps = $ulps:expr $(,)?) => {
+        $crate::assert_abs(&$left, &$right, Some($epsilon), Some($ulps))
+    };
+}
+
+/// Asserts that two math values are component-wise within a margin relative
+/// to their magnitude, `max_relative = ..` (`Scalar::DEFAULT_MAX_RELATIVE` by
+/// default). The panic message lists the components that differ.
+#[macro_export]
+macro_rules! assert_rel_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_rel(&$left, &$right, None)
+    };
+    ($left:expr, $right:expr, max_relative = $max_relative:expr $(,)?) => {
+        $crate::assert_rel(&$left, &$right, Some($max_relative))
+    };
+}
@@ -1,13 +1,23 @@
 use std::ops;
 
 use crate::{
-    impl_algebraic_ops, impl_conversions, impl_index_ops, mat3::Mat3, mat4::Mat4, vec3::Vec3,
+    affine3::Affine3, dmat3::DMat3, dmat4::DMat4, dvec3::DVec3, dvec4::DVec4, impl_algebraic_ops,
+    impl_conversions, impl_index_ops, mat2::Mat2, mat3::Mat3, mat4::Mat4, vec2::Vec2, vec3::Vec3,
     vec4::Vec4,
 };
 
+impl_algebraic_ops!(Mat2 { a, b }, Vec2);
 impl_algebraic_ops!(Mat3 { a, b, c }, Vec3);
 impl_algebraic_ops!(Mat4 { a, b, c, d }, Vec4);
+impl_algebraic_ops!(DMat3 { a, b, c }, DVec3, f64);
+impl_algebraic_ops!(DMat4 { a, b, c, d }, DVec4, f64);
 
+impl_conversions!(Mat2 => [[f32; 2]; 2], |m: &Mat2| {
+    [m.a.into(), m.b.into()]
+});
+impl_conversions!([[f32; 2]; 2] => Mat2, |a: &[[f32; 2]; 2]| {
+    Mat2::from_columns(a[0].into(), a[1].into())
+});
 impl_conversions!(Mat3 => [[f32; 3]; 3], |m: &Mat3| {
     [m.a.into(), m.b.into(), m.c.into()]
 });
@@ -21,5 +31,51 @@ impl_conversions!([[f32; 4]; 4] => Mat4, |a: &[[f32; 4]; 4]| {
     Mat4::from_columns(a[0].into(), a[1].into(), a[2].into(), a[3].into())
 });
 
+impl_conversions!(Affine3 => [[f32; 4]; 3], |m: &Affine3| {
+    [m.a.into(), m.b.into(), m.c.into()]
+});
+impl_conversions!([[f32; 4]; 3] => Affine3, |a: &[[f32; 4]; 3]| {
+    Affine3 {
+        a: a[0].into(),
+        b: a[1].into(),
+        c: a[2].into(),
+    }
+});
+
+impl_conversions!(DMat3 => [[f64; 3]; 3], |m: &DMat3| {
+    [m.a.into(), m.b.into(), m.c.into()]
+});
+impl_conversions!([[f64; 3]; 3] => DMat3, |a: &[[f64; 3]; 3]| {
+    DMat3::from_columns(a[0].into(), a[1].into(), a[2].into())
+});
+impl_conversions!(DMat4 => [[f64; 4]; 4], |m: &DMat4| {
+    [m.a.into(), m.b.into(), m.c.into(), m.d.into()]
+});
+impl_conversions!([[f64; 4]; 4] => DMat4, |a: &[[f64; 4]; 4]| {
+    DMat4::from_columns(a[0].into(), a[1].into(), a[2].into(), a[3].into())
+});
+
+impl_conversions!(Mat4 => Mat3, |m: &Mat4| {
+    Mat3::from_columns(m.a.xyz(), m.b.xyz(), m.c.xyz())
+});
+
+impl_conversions!(Mat3 => Mat4, |m: &Mat3| {
+    Mat4::from_columns(m.a.extend(0.), m.b.extend(0.), m.c.extend(0.), Vec4::W)
+});
+
+impl_conversions!(Affine3 => Mat4, |m: &Affine3| {
+    Mat4::from_columns(m.a, m.b, m.c, Vec4::W)
+});
+
+impl_conversions!(Mat3 => DMat3, |m: &Mat3| {
+    DMat3::from_columns(m.a.into(), m.b.into(), m.c.into())
+});
+impl_conversions!(Mat4 => DMat4, |m: &Mat4| {
+    DMat4::from_columns(m.a.into(), m.b.into(), m.c.into(), m.d.into())
+});
+
+impl_index_ops!(Mat2 { 0 => a, 1 => b } => Vec2);
 impl_index_ops!(Mat3 { 0 => a, 1 => b, 2 => c } => Vec3);
 impl_index_ops!(Mat4 { 0 => a, 1 => b, 2 => c, 3 => d } => Vec4);
+impl_index_ops!(DMat3 { 0 => a, 1 => b, 2 => c } => DVec3);
+impl_index_ops!(DMat4 { 0 => a, 1 => b, 2 => c, 3 => d } => DVec4);
@@ -0,0 +1,149 @@
+use std::ops;
+
+use crate::{
+    impl_op, impl_op_assign,
+    precision::is_invertible,
+    structure::{EuclideanSpace, Mat, SquareMat, VecSpace},
+    vec2::Vec2,
+};
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct Mat2 {
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
+impl Mat2 {
+    pub fn new(c0r0: f32, c0r1: f32, c1r0: f32, c1r1: f32) -> Self {
+        Self::from_columns([c0r0, c0r1].into(), [c1r0, c1r1].into())
+    }
+
+    pub fn from_columns(a: Vec2, b: Vec2) -> Self {
+        Self { a, b }
+    }
+
+    pub fn from_angle(t: f32) -> Self {
+        let (s, c) = t.sin_cos();
+        Self::new(
+            c, -s, //
+            s, c,
+        )
+    }
+
+    pub fn make_scale(s: Vec2) -> Self {
+        Self::from_diagonal(s)
+    }
+}
+
+impl Mat for Mat2 {
+    type Scalar = f32;
+    type Row = Vec2;
+    type Column = Vec2;
+    type Transpose = Mat2;
+
+    const ZERO: Self = Self {
+        a: Vec2::ZERO,
+        b: Vec2::ZERO,
+    };
+
+    fn transpose(&self) -> Self {
+        Self::new(self.a.x, self.b.x, self.a.y, self.b.y)
+    }
+}
+
+impl SquareMat for Mat2 {
+    type RowColumn = Vec2;
+
+    const IDENTITY: Self = Self {
+        a: Vec2::X,
+        b: Vec2::Y,
+    };
+
+    fn from_diagonal(d: Self::RowColumn) -> Self {
+        Self::new(d.x, 0., 0., d.y)
+    }
+
+    fn determinant(&self) -> f32 {
+        self.a.perp_dot(&self.b)
+    }
+
+    fn adjugate(&self) -> Self {
+        Self::new(self.b.y, -self.a.y, -self.b.x, self.a.x)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        let det = self.determinant();
+        let t = self.transpose();
+
+        if is_invertible(det, &[t.a.norm(), t.b.norm()]) {
+            Some(self.adjugate() * (1. / det))
+        } else {
+            None
+        }
+    }
+
+    fn norm_1(&self) -> f32 {
+        f32::max(
+            self.a.x.abs() + self.b.x.abs(),
+            self.a.y.abs() + self.b.y.abs(),
+        )
+    }
+}
+
+impl_op!(Mat2 : Mat2, ops::Mul { fn mul |lhs: &Mat2, rhs: &Mat2| {
+    let t = rhs.transpose();
+    Mat2::new(
+        lhs.a.dot(&t.a), lhs.a.dot(&t.b), //
+        lhs.b.dot(&t.a), lhs.b.dot(&t.b),
+    )
+}});
+
+impl_op_assign!(Mat2, Mat2, ops::MulAssign { fn mul_assign |lhs: &mut Mat2, rhs: &Mat2| {
+    *lhs = *lhs * rhs;
+}});
+
+impl_op!(Mat2 : Vec2 => Vec2, ops::Mul { fn mul |lhs: &Mat2, rhs: &Vec2| {
+    Vec2::new(lhs.a.dot(rhs), lhs.b.dot(rhs))
+}});
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn indexes_rows() {
+        let m = Mat2::new(1., 2., 3., 4.);
+        assert_eq!(m[0][1], 2.);
+        assert_eq!(m[1][0], 3.);
+        assert_eq!(m.transpose(), Mat2::new(1., 3., 2., 4.));
+    }
+
+    #[test]
+    fn multiplies() {
+        let a = Mat2::new(1., 2., 3., 4.);
+        let b = Mat2::new(0., 1., 1., 0.);
+        assert_eq!(a * b, Mat2::new(2., 1., 4., 3.));
+        assert_eq!(a * Vec2::new(1., 1.), Vec2::new(3., 7.));
+    }
+
+    #[test]
+    fn inverts() {
+        let m = Mat2::new(4., 7., 2., 6.);
+        assert_eq!(m.determinant(), 10.);
+        assert_eq!(m.invert().unwrap(), Mat2::new(0.6, -0.7, -0.2, 0.4));
+        assert_eq!(m * m.invert().unwrap(), Mat2::IDENTITY);
+        assert!(Mat2::new(1., 2., 2., 4.).invert().is_none());
+    }
+
+    #[test]
+    fn rotates_counter_clockwise() {
+        assert_eq!(Mat2::from_angle(FRAC_PI_2) * Vec2::X, Vec2::Y);
+        assert_eq!(
+            Mat2::make_scale(Vec2::new(2., 3.)) * Vec2::new(1., 1.),
+            Vec2::new(2., 3.)
+        );
+    }
+}
@@ -2,11 +2,13 @@ use std::ops;
 
 use crate::{
     impl_op, impl_op_assign,
-    structure::{EuclideanSpace, Mat, SquareMat},
+    precision::is_invertible,
+    structure::{EuclideanSpace, Mat, SquareMat, VecSpace},
     vec3::Vec3,
 };
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "gpu", repr(C))]
 pub struct Mat3 {
     pub a: Vec3,
     pub b: Vec3,
@@ -130,9 +132,76 @@ impl Mat3 {
         ]
         .into()
     }
+
+    /// Eigenvalues and unit eigenvectors of a symmetric matrix, computed
+    /// with cyclic Jacobi rotations (Ericson, RTCD section 4.4.4). Only the
+    /// upper triangle is read.
+    pub fn symmetric_eigen(&self) -> (Vec3, [Vec3; 3]) {
+        let mut a = *self;
+        a[1][0] = a[0][1];
+        a[2][0] = a[0][2];
+        a[2][1] = a[1][2];
+        let mut v = Self::IDENTITY;
+
+        for _ in 0..32 {
+            // Rotate away the largest off-diagonal element
+            let (p, q) = [(0, 1), (0, 2), (1, 2)]
+                .into_iter()
+                .max_by(|&(i, j), &(k, l)| a[i][j].abs().total_cmp(&a[k][l].abs()))
+                .unwrap();
+            let off = a[p][q];
+            let scale = a[0][0].abs() + a[1][1].abs() + a[2][2].abs();
+            if off.abs() <= f32::EPSILON * scale || off == 0. {
+                break;
+            }
+
+            let r = (a[q][q] - a[p][p]) / (2. * off);
+            let t = r.signum() / (r.abs() + r.hypot(1.));
+            let c = 1. / t.hypot(1.);
+            let s = t * c;
+
+            let mut j = Self::IDENTITY;
+            j[p][p] = c;
+            j[q][q] = c;
+            j[p][q] = s;
+            j[q][p] = -s;
+
+            a = j.transpose() * a * j;
+            v *= j;
+        }
+
+        let column = |k: usize| Vec3::new(v[0][k], v[1][k], v[2][k]);
+        (
+            Vec3::new(a[0][0], a[1][1], a[2][2]),
+            [column(0), column(1), column(2)],
+        )
+    }
+
+    /// Splits the matrix into `Q * S` with `Q` orthogonal and `S` symmetric
+    /// positive definite, using Higham's scaled Newton iteration. `Q` is a
+    /// reflection when the determinant is negative. `None` for singular
+    /// matrices.
+    pub fn polar_decompose(&self) -> Option<(Self, Self)> {
+        let mut q = *self;
+        for _ in 0..32 {
+            let inv_t = q.invert()?.transpose();
+            // Scaling speeds up convergence for badly conditioned matrices
+            let gamma = (inv_t.norm_1() / q.norm_1()).sqrt();
+            let next = (q * gamma + inv_t * (1. / gamma)) * 0.5;
+            let change = (next - q).norm_1();
+            q = next;
+            if change <= f32::EPSILON * 4. {
+                break;
+            }
+        }
+
+        let s = q.transpose() * self;
+        Some((q, (s + s.transpose()) * 0.5))
+    }
 }
 
 impl Mat for Mat3 {
+    type Scalar = f32;
     type Row = Vec3;
     type Column = Vec3;
     type Transpose = Mat3;
@@ -177,22 +246,31 @@ impl SquareMat for Mat3 {
             + m[2][0] * (m[0][1] * m[1][2] - m[1][1] * m[0][2])
     }
 
-    fn invert(&self) -> Option<Self> {
+    fn adjugate(&self) -> Self {
         let Self { a, b, c } = self;
+        Self::from_columns(b.cross(c), c.cross(a), a.cross(b)).transpose()
+    }
 
-        let r0 = b.cross(c);
-        let r1 = c.cross(a);
-        let r2 = a.cross(b);
+    fn invert(&self) -> Option<Self> {
+        let Self { a, b, c } = self;
 
-        let det = r2.dot(c);
+        let det = a.cross(b).dot(c);
+        let t = self.transpose();
 
-        if det != 0. {
-            let inv_det = 1. / r2.dot(c);
-            Some(Self::from_columns(r0 * inv_det, r1 * inv_det, r2 * inv_det))
+        if is_invertible(det, &[t.a.norm(), t.b.norm(), t.c.norm()]) {
+            Some(self.adjugate() * (1. / det))
         } else {
             None
         }
     }
+
+    fn norm_1(&self) -> f32 {
+        let t = self.transpose();
+        [t.a, t.b, t.c]
+            .iter()
+            .map(|v| v.x.abs() + v.y.abs() + v.z.abs())
+            .fold(0., f32::max)
+    }
 }
 
 impl_op!(Mat3 : Mat3, ops::Mul { fn mul |lhs: &Mat3, rhs: &Mat3| {
@@ -200,7 +278,7 @@ impl_op!(Mat3 : Mat3, ops::Mul { fn mul |lhs: &Mat3, rhs: &Mat3| {
     for i in 0..3 {
         for j in 0..3 {
             for k in 0..3 {
-                m[i][j] += lhs[k][j] * rhs[i][k];
+                m[i][j] += lhs[i][k] * rhs[k][j];
             }
         }
     }
@@ -217,13 +295,62 @@ impl_op!(Mat3 : Vec3 => Vec3, ops::Mul { fn mul |lhs: &Mat3, rhs: &Vec3| {
 
 #[cfg(test)]
 mod tests {
+    use float_cmp::approx_eq;
+
     use crate::{
+        precision::PRECISION,
         structure::{Mat, SquareMat, VecSpace},
         vec3::Vec3,
     };
 
     use super::Mat3;
 
+    #[test]
+    fn decomposes_symmetric_matrix() {
+        let m = Mat3::new(
+            4., 1., 2., //
+            1., 3., 0., //
+            2., 0., 5.,
+        );
+        let (values, vectors) = m.symmetric_eigen();
+        for k in 0..3 {
+            assert_eq!(m * vectors[k], vectors[k] * values[k]);
+            assert!(approx_eq!(f32, vectors[k].norm(), 1., PRECISION));
+        }
+        assert!(approx_eq!(
+            f32,
+            values.x + values.y + values.z,
+            12.,
+            PRECISION
+        ));
+
+        let (values, _) = Mat3::from_diagonal(Vec3::new(1., 2., 3.)).symmetric_eigen();
+        assert_eq!(values, Vec3::new(1., 2., 3.));
+    }
+
+    #[test]
+    fn polar_decomposes() {
+        let shear = Mat3::new(
+            1., 0.5, 0., //
+            0., 1., 0., //
+            0., 0., 1.,
+        );
+        let m = Mat3::from_angle_x(0.7) * shear * Mat3::from_diagonal(Vec3::new(2., -1., 3.));
+        let (q, s) = m.polar_decompose().unwrap();
+        assert_eq!(q * s, m);
+        assert_eq!(q * q.transpose(), Mat3::IDENTITY);
+        assert!(approx_eq!(f32, q.determinant(), -1., PRECISION));
+        assert_eq!(s, s.transpose());
+        let (values, _) = s.symmetric_eigen();
+        assert!(values.x > 0. && values.y > 0. && values.z > 0.);
+
+        let r = Mat3::from_angle_y(-1.2);
+        let (q, s) = r.polar_decompose().unwrap();
+        assert_eq!(q, r);
+        assert_eq!(s, Mat3::IDENTITY);
+        assert!(Mat3::ZERO.polar_decompose().is_none());
+    }
+
     #[test]
     fn new_works() {
         let m = Mat3::new(
@@ -303,6 +430,14 @@ mod tests {
         )
     }
 
+    #[test]
+    fn matrix_multiplication_composes_right_to_left() {
+        let x = Mat3::from_angle_x(0.5);
+        let z = Mat3::from_angle_z(1.2);
+        let v = Vec3::new(1., 2., 3.);
+        assert_eq!((z * x) * v, z * (x * v));
+    }
+
     #[test]
     fn transposes() {
         let m = Mat3::new(
@@ -338,15 +473,41 @@ mod tests {
             5., 1., 0., //
             0., 1., 3.,
         );
+        let inv = m.invert().unwrap();
         assert_eq!(
-            m.invert().unwrap(),
+            inv,
             [
-                [3., -15., 5.], //
-                [-1., 6., -2.], //
-                [1., -5., 2.]
+                [3., -1., 1.],   //
+                [-15., 6., -5.], //
+                [5., -2., 2.]
             ]
             .into()
         );
+        assert_eq!(m * inv, Mat3::IDENTITY);
+        assert_eq!(inv * m, Mat3::IDENTITY);
+    }
+
+    #[test]
+    fn rejects_singular_inverse() {
+        let m = Mat3::new(
+            1., 2., 3., //
+            4., 5., 6., //
+            7., 8., 9.,
+        );
+        assert!(m.invert().is_none());
+        assert_eq!(m.invert_or_identity(), Mat3::IDENTITY);
+        assert_eq!(m.condition(), f32::INFINITY);
+    }
+
+    #[test]
+    fn calculates_adjugate() {
+        let m = Mat3::new(
+            2., 0., -1., //
+            5., 1., 0., //
+            0., 1., 3.,
+        );
+        assert_eq!(m * m.adjugate(), Mat3::IDENTITY * m.determinant());
+        assert_eq!(m.cofactor(), m.adjugate().transpose());
     }
 
     #[test]
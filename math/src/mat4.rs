@@ -1,12 +1,15 @@
 use crate::{
     impl_op,
-    structure::{EuclideanSpace, Mat, SquareMat},
+    precision::is_invertible,
+    simd,
+    structure::{EuclideanSpace, Mat, SquareMat, VecSpace},
     vec3::Vec3,
     vec4::Vec4,
 };
 use std::ops;
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "gpu", repr(C))]
 pub struct Mat4 {
     pub a: Vec4,
     pub b: Vec4,
@@ -18,9 +21,40 @@ impl Mat4 {
     pub fn from_columns(a: Vec4, b: Vec4, c: Vec4, d: Vec4) -> Self {
         Self { a, b, c, d }
     }
+
+    /// Whether the bottom row is exactly `[0, 0, 0, 1]`, so that the matrix
+    /// converts to an `Affine3` without loss.
+    pub fn is_affine(&self) -> bool {
+        // Warning: not `==`, whose tolerance would let a slightly projective
+        // row through
+        let Vec4 { x, y, z, w } = self.d;
+        x == 0. && y == 0. && z == 0. && w == 1.
+    }
+
+    // The four 3D vectors shared by the determinant and the adjugate,
+    // see Lengyel, FGED1 section 1.7.5
+    fn partials(&self) -> (Vec3, Vec3, Vec3, Vec3) {
+        let a: Vec3 = self.a.into();
+        let b: Vec3 = self.b.into();
+        let c: Vec3 = self.c.into();
+        let d: Vec3 = self.d.into();
+
+        let x = self[0][3];
+        let y = self[1][3];
+        let z = self[2][3];
+        let w = self[3][3];
+
+        let s = a.cross(&b);
+        let t = c.cross(&d);
+        let u = a * y - b * x;
+        let v = c * w - d * z;
+
+        (s, t, u, v)
+    }
 }
 
 impl Mat for Mat4 {
+    type Scalar = f32;
     type Row = Vec4;
     type Column = Vec4;
     type Transpose = Mat4;
@@ -33,6 +67,9 @@ impl Mat for Mat4 {
     };
 
     fn transpose(&self) -> Self::Transpose {
+        if cfg!(feature = "simd") {
+            return simd::mat4_transpose(self);
+        }
         let m = self;
         [
             [m[0][0], m[1][0], m[2][0], m[3][0]],
@@ -64,10 +101,11 @@ impl SquareMat for Mat4 {
     }
 
     fn determinant(&self) -> f32 {
-        todo!()
+        let (s, t, u, v) = self.partials();
+        s.dot(&v) + t.dot(&u)
     }
 
-    fn invert(&self) -> Option<Self> {
+    fn adjugate(&self) -> Self {
         let a: Vec3 = self.a.into();
         let b: Vec3 = self.b.into();
         let c: Vec3 = self.c.into();
@@ -78,38 +116,55 @@ impl SquareMat for Mat4 {
         let z = self[2][3];
         let w = self[3][3];
 
-        let mut s = a.cross(&b);
-        let mut t = c.cross(&d);
-        let mut u = a * y - b * x;
-        let mut v = c * w - d * z;
-
-        let det = s.dot(&v) + t.dot(&u);
-
-        if det != 0. {
-            let inv_det = 1. / det;
-            s *= inv_det;
-            t *= inv_det;
-            u *= inv_det;
-            v *= inv_det;
-
-            let r0 = b.cross(&v) + t * y;
-            let r1 = v.cross(&a) - t * x;
-            let r2 = d.cross(&u) + s * w;
-            let r3 = u.cross(&c) - s * z;
-
-            Some(Self::from_columns(
-                [r0.x, r0.y, r0.z, -b.dot(&t)].into(),
-                [r1.x, r1.y, r1.z, a.dot(&t)].into(),
-                [r2.x, r2.y, r2.z, -d.dot(&s)].into(),
-                [r3.x, r3.y, r3.z, c.dot(&s)].into(),
-            ))
+        let (s, t, u, v) = self.partials();
+
+        let r0 = b.cross(&v) + t * y;
+        let r1 = v.cross(&a) - t * x;
+        let r2 = d.cross(&u) + s * w;
+        let r3 = u.cross(&c) - s * z;
+
+        Self::from_columns(
+            [r0.x, r0.y, r0.z, -b.dot(&t)].into(),
+            [r1.x, r1.y, r1.z, a.dot(&t)].into(),
+            [r2.x, r2.y, r2.z, -d.dot(&s)].into(),
+            [r3.x, r3.y, r3.z, c.dot(&s)].into(),
+        )
+        .transpose()
+    }
+
+    fn invert(&self) -> Option<Self> {
+        if cfg!(feature = "simd") {
+            return simd::mat4_invert(self);
+        }
+        let det = self.determinant();
+        let t = self.transpose();
+        let mut norms = [t.a.norm(), t.b.norm(), t.c.norm(), t.d.norm()];
+        if self.is_affine() {
+            // The translation does not affect invertibility, only the
+            // linear part is weighed
+            norms[3] = 1.;
+        }
+
+        if is_invertible(det, &norms) {
+            Some(self.adjugate() * (1. / det))
         } else {
             None
         }
     }
+
+    fn norm_1(&self) -> f32 {
+        let t = self.transpose();
+        [t.a, t.b, t.c, t.d]
+            .iter()
+            .map(|v| v.x.abs() + v.y.abs() + v.z.abs() + v.w.abs())
+            .fold(0., f32::max)
+    }
 }
 
 impl_op!(Mat4 : Mat4, ops::Mul { fn mul |a: &Mat4, b: &Mat4| {
+    if cfg!(feature = "simd") {
+        return simd::mat4_mul(a, b);
+    }
     let mut m = Mat4::ZERO;
     for i in 0..4 {
         for j in 0..4 {
@@ -122,6 +177,9 @@ impl_op!(Mat4 : Mat4, ops::Mul { fn mul |a: &Mat4, b: &Mat4| {
 }});
 
 impl_op!(Mat4 : Vec4 => Vec4, ops::Mul { fn mul |m: &Mat4, v: &Vec4| {
+    if cfg!(feature = "simd") {
+        return simd::mat4_mul_vec4(m, v);
+    }
     Vec4::new(v.dot(&m.a), v.dot(&m.b), v.dot(&m.c), v.dot(&m.d))
 }});
 
@@ -181,12 +239,109 @@ mod tests {
         .into();
         let inv = mat4.invert().unwrap();
         let expected: Mat4 = [
-            [0.16, 0.28, -0.68, 0.28],
-            [-0.26, -3.08, 4.48, -1.08],
-            [-0.1, 0.2, -0.2, 0.2],
-            [0.16, 1.28, -1.68, 0.28],
+            [0.16, -0.26, -0.1, 0.16],
+            [0.28, -3.08, 0.2, 1.28],
+            [-0.68, 4.48, -0.2, -1.68],
+            [0.28, -1.08, 0.2, 0.28],
         ]
         .into();
         assert_eq!(inv, expected);
+        assert_eq!(mat4 * inv, Mat4::IDENTITY);
+        assert_eq!(inv * mat4, Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn calculates_determinant() {
+        let mat4: Mat4 = [
+            [6., 1., 2., 4.],
+            [4., 3., 3., 2.],
+            [2., 5., 5., 6.],
+            [8., 7., 6., 3.],
+        ]
+        .into();
+        assert_eq!(mat4.determinant(), 50.);
+        assert_eq!(Mat4::IDENTITY.determinant(), 1.);
+        assert_eq!(
+            Mat4::from_diagonal([2., 3., 4., 5.].into()).determinant(),
+            120.
+        );
+    }
+
+    #[test]
+    fn calculates_adjugate() {
+        let mat4: Mat4 = [
+            [6., 1., 2., 4.],
+            [4., 3., 3., 2.],
+            [2., 5., 5., 6.],
+            [8., 7., 6., 3.],
+        ]
+        .into();
+        let det = mat4.determinant();
+        assert_eq!(mat4 * mat4.adjugate(), Mat4::IDENTITY * det);
+        assert_eq!(mat4.cofactor(), mat4.adjugate().transpose());
+    }
+
+    #[test]
+    fn rejects_near_singular_inverse() {
+        let mat4: Mat4 = [
+            [1., 2., 3., 4.],
+            [5., 6., 7., 8.],
+            [9., 10., 11., 12.],
+            [13., 14., 15., 16.],
+        ]
+        .into();
+        assert!(mat4.invert().is_none());
+        assert_eq!(mat4.invert_or_identity(), Mat4::IDENTITY);
+        assert_eq!(mat4.condition(), f32::INFINITY);
+
+        // The first two columns are nearly parallel
+        let nearly: Mat4 = [
+            [1., 1., 0., 0.],
+            [0., 1e-7, 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., 0., 1.],
+        ]
+        .into();
+        assert!(nearly.invert().is_none());
+    }
+
+    #[test]
+    fn inverts_small_scale_matrices() {
+        // A uniformly tiny matrix is perfectly conditioned even though
+        // its determinant underflows any fixed threshold
+        let mat4 = Mat4::from_diagonal([1e-3, 1e-3, 1e-3, 1e-3].into());
+        let inv = mat4.invert().unwrap();
+        assert_eq!(inv, Mat4::from_diagonal([1e3, 1e3, 1e3, 1e3].into()));
+    }
+
+    #[test]
+    fn inverts_scaled_far_translated_matrices() {
+        // The translation only lengthens the last column, so a small model
+        // matrix far from the origin stays invertible
+        let mut mat4 = Mat4::from_diagonal([0.01, 0.01, 0.01, 1.].into());
+        mat4.a.w = 1000.;
+        mat4.b.w = 1000.;
+        mat4.c.w = 1000.;
+        let inv = mat4.invert().unwrap();
+        assert_eq!(mat4 * inv, Mat4::IDENTITY);
+        assert_eq!(inv.a, Vec4::new(100., 0., 0., -1e5));
+    }
+
+    #[test]
+    fn inverts_large_translations() {
+        // det is 1 while the translation column is a million units long
+        let mut mat4 = Mat4::IDENTITY;
+        mat4.a.w = 1e6;
+        let inv = mat4.invert().unwrap();
+        assert_eq!(inv.a, Vec4::new(1., 0., 0., -1e6));
+        assert_eq!(inv, crate::transform4::Transform4::invert(&mat4).unwrap());
+        assert!(mat4.condition().is_finite());
+    }
+
+    #[test]
+    fn estimates_condition() {
+        assert_eq!(Mat4::IDENTITY.condition(), 1.);
+        let mat4 = Mat4::from_diagonal([1., 2., 4., 100.].into());
+        assert_eq!(mat4.condition(), 100.);
     }
 }
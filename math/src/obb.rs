@@ -0,0 +1,290 @@
+use crate::{
+    aabb::Aabb,
+    mat3::Mat3,
+    mat4::Mat4,
+    precision::SINGULAR_EPSILON,
+    pt3::Pt3,
+    structure::{EuclideanSpace, Mat, VecSpace},
+    transform4::T4Mul,
+    vec3::Vec3,
+};
+
+/// Oriented bounding box. `axes` are orthonormal and right-handed, and the
+/// box spans `half_extents[k]` along `axes[k]` on both sides of `center`.
+#[derive(Clone, Copy, Debug)]
+pub struct Obb {
+    pub center: Pt3,
+    pub axes: [Vec3; 3],
+    pub half_extents: Vec3,
+}
+
+impl Obb {
+    pub fn new(center: Pt3, axes: [Vec3; 3], half_extents: Vec3) -> Self {
+        Self {
+            center,
+            axes,
+            half_extents,
+        }
+    }
+
+    pub fn from_aabb(b: &Aabb) -> Self {
+        Self::new(b.center(), [Vec3::X, Vec3::Y, Vec3::Z], b.half_extents())
+    }
+
+    /// Fits a box to `points` along the principal axes of their covariance.
+    /// The fit is tight along those axes but not the minimal-volume box.
+    /// `None` when there are no points.
+    pub fn from_points(points: &[Pt3]) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        let n = points.len() as f32;
+        let mean = points.iter().fold(Vec3::ZERO, |acc, p| acc + Vec3::from(p)) / n;
+
+        let mut cov = Mat3::ZERO;
+        for p in points {
+            let d = Vec3::from(p) - mean;
+            for i in 0..3 {
+                for j in i..3 {
+                    cov[i][j] += d[i] * d[j] / n;
+                }
+            }
+        }
+        let (_, [u, v, _]) = cov.symmetric_eigen();
+        let axes = [u, v, u.cross(&v)];
+
+        let mut lo = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut hi = -lo;
+        for p in points {
+            let d = Vec3::from(p) - mean;
+            for k in 0..3 {
+                let t = d.dot(&axes[k]);
+                lo[k] = lo[k].min(t);
+                hi[k] = hi[k].max(t);
+            }
+        }
+
+        let mid = (lo + hi) * 0.5;
+        let center = mean + axes[0] * mid.x + axes[1] * mid.y + axes[2] * mid.z;
+        Some(Self::new(center.into(), axes, (hi - lo) * 0.5))
+    }
+
+    /// Rotation taking the box frame to world space; its columns are `axes`.
+    pub fn rotation(&self) -> Mat3 {
+        let [a, b, c] = self.axes;
+        Mat3::new(
+            a.x, b.x, c.x, //
+            a.y, b.y, c.y, //
+            a.z, b.z, c.z,
+        )
+    }
+
+    pub fn corners(&self) -> [Pt3; 8] {
+        let [a, b, c] = self.axes;
+        let h = self.half_extents;
+        let (a, b, c) = (a * h.x, b * h.y, c * h.z);
+        [
+            self.center - a - b - c,
+            self.center + a - b - c,
+            self.center - a + b - c,
+            self.center + a + b - c,
+            self.center - a - b + c,
+            self.center + a - b + c,
+            self.center - a + b + c,
+            self.center + a + b + c,
+        ]
+    }
+
+    /// Smallest axis-aligned box containing this box.
+    pub fn to_aabb(&self) -> Aabb {
+        let h = self.half_extents;
+        let e = (0..3).fold(Vec3::ZERO, |acc, k| acc + (self.axes[k] * h[k]).abs());
+        Aabb::from_center(self.center, e)
+    }
+
+    /// A box containing both boxes, fitted to their corners.
+    pub fn union(&self, other: &Obb) -> Self {
+        let mut corners = [self.center; 16];
+        corners[..8].copy_from_slice(&self.corners());
+        corners[8..].copy_from_slice(&other.corners());
+        Self::from_points(&corners).unwrap()
+    }
+
+    /// Grows the box by `margin` on every side, shrinking for negative values.
+    pub fn expand(&self, margin: f32) -> Self {
+        let m = Vec3::new(margin, margin, margin);
+        Self::new(self.center, self.axes, self.half_extents + m)
+    }
+
+    /// `p` in the box frame, relative to the center.
+    pub fn to_local(&self, p: &Pt3) -> Vec3 {
+        let d = Vec3::from(*p - self.center);
+        Vec3::new(
+            d.dot(&self.axes[0]),
+            d.dot(&self.axes[1]),
+            d.dot(&self.axes[2]),
+        )
+    }
+
+    pub fn contains_point(&self, p: &Pt3) -> bool {
+        let l = self.to_local(p);
+        let h = self.half_extents;
+        l.x.abs() <= h.x && l.y.abs() <= h.y && l.z.abs() <= h.z
+    }
+
+    pub fn contains(&self, other: &Obb) -> bool {
+        other.corners().iter().all(|p| self.contains_point(p))
+    }
+
+    /// Point of the box closest to `p`.
+    pub fn closest_point(&self, p: &Pt3) -> Pt3 {
+        let l = self.to_local(p);
+        let h = self.half_extents;
+        (0..3).fold(self.center, |q, k| {
+            q + self.axes[k] * l[k].clamp(-h[k], h[k])
+        })
+    }
+
+    /// Box around the affine image of this box.
+    pub fn transform(&self, m: &Mat4) -> Self {
+        // Warning: with shear or a non-uniform scale that is not aligned with
+        // the axes the image is not a box; the axes are re-orthonormalized
+        // and the extents grown so that the result still bounds it
+        let l = Mat3::from(m);
+        let axes = orthonormalize(self.axes.map(|a| l * a));
+        let h = self.half_extents;
+        let images = [0, 1, 2].map(|k| l * (self.axes[k] * h[k]));
+        let half_extents = Vec3::new(
+            images.iter().map(|v| v.dot(&axes[0]).abs()).sum(),
+            images.iter().map(|v| v.dot(&axes[1]).abs()).sum(),
+            images.iter().map(|v| v.dot(&axes[2]).abs()).sum(),
+        );
+        Self::new(T4Mul::mul(m, &self.center), axes, half_extents)
+    }
+}
+
+/// Right-handed orthonormal frame following `dirs` by Gram-Schmidt. Directions
+/// that vanish or fall into the span of the earlier ones, as under a singular
+/// map, are skipped in favour of the next direction or a world axis.
+fn orthonormalize(dirs: [Vec3; 3]) -> [Vec3; 3] {
+    let mut frame = [Vec3::ZERO; 2];
+    let mut n = 0;
+    for v in dirs.into_iter().chain([Vec3::X, Vec3::Y, Vec3::Z]) {
+        let r = frame[..n].iter().fold(v, |r, a| r - *a * a.dot(&r));
+        if v.norm2() > 0. && r.norm2() > SINGULAR_EPSILON * v.norm2() {
+            frame[n] = r.normalize();
+            n += 1;
+            if n == 2 {
+                break;
+            }
+        }
+    }
+    let [a, b] = frame;
+    [a, b, a.cross(&b)]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use float_cmp::approx_eq;
+
+    use crate::{
+        assert_approx_eq, precision::PRECISION, structure::SquareMat, transform4::Transform4,
+    };
+
+    use super::*;
+
+    fn rotated_box_points() -> Vec<Pt3> {
+        // A 4 x 2 x 1 box turned 45 degrees about z and moved off the origin
+        let r = Mat3::from_angle_z(FRAC_PI_4);
+        let mut points = Vec::new();
+        for i in 0..=4 {
+            for j in 0..=2 {
+                for k in 0..=1 {
+                    let p = Vec3::new(i as f32 - 2., j as f32 - 1., k as f32 - 0.5);
+                    points.push(Pt3::from(r * p + Vec3::new(1., 2., 3.)));
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn fits_principal_axes() {
+        let b = Obb::from_points(&rotated_box_points()).unwrap();
+        let mut h = [b.half_extents.x, b.half_extents.y, b.half_extents.z];
+        h.sort_by(f32::total_cmp);
+        assert!(approx_eq!(f32, h[0], 0.5, PRECISION));
+        assert!(approx_eq!(f32, h[1], 1., PRECISION));
+        assert!(approx_eq!(f32, h[2], 2., PRECISION));
+        assert_approx_eq!(b.center, Pt3::new(1., 2., 3.));
+        assert!(approx_eq!(f32, b.rotation().determinant(), 1., PRECISION));
+        for p in rotated_box_points() {
+            assert!(b.expand(1e-4).contains_point(&p));
+        }
+        assert!(Obb::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn converts_to_and_from_aabb() {
+        let a = Aabb::new(Pt3::new(0., 0., 0.), Pt3::new(2., 4., 6.));
+        let b = Obb::from_aabb(&a);
+        assert_eq!(b.half_extents, Vec3::new(1., 2., 3.));
+        let back = b.to_aabb();
+        assert_approx_eq!(back.min, a.min);
+        assert_approx_eq!(back.max, a.max);
+    }
+
+    #[test]
+    fn merges_and_contains() {
+        let a = Obb::from_aabb(&Aabb::new(Pt3::new(0., 0., 0.), Pt3::new(1., 1., 1.)));
+        let b = Obb::from_aabb(&Aabb::new(Pt3::new(3., 3., 0.), Pt3::new(4., 4., 1.)));
+        let u = a.union(&b).expand(1e-4);
+        assert!(u.contains(&a) && u.contains(&b));
+        assert!(!a.contains(&b));
+        assert_approx_eq!(
+            a.closest_point(&Pt3::new(2., 0.5, -1.)),
+            Pt3::new(1., 0.5, 0.)
+        );
+    }
+
+    #[test]
+    fn transforms() {
+        let b = Obb::from_aabb(&Aabb::new(Pt3::new(-1., -2., -3.), Pt3::new(1., 2., 3.)));
+        let mut m =
+            Mat4::from(Mat3::from_angle_z(FRAC_PI_4) * Mat3::from_diagonal(Vec3::new(2., 2., 2.)));
+        m.set_translation(Vec3::new(5., 0., 0.));
+        let t = b.transform(&m);
+        assert_approx_eq!(t.center, Pt3::new(5., 0., 0.));
+        assert_eq!(t.half_extents, Vec3::new(2., 4., 6.));
+        for p in b.corners() {
+            assert!(t.expand(1e-4).contains_point(&T4Mul::mul(&m, &p)));
+        }
+    }
+
+    #[test]
+    fn transforms_flat_boxes() {
+        // A floor quad has no thickness along x
+        let b = Obb::from_aabb(&Aabb::new(Pt3::new(0., -1., -1.), Pt3::new(0., 1., 1.)));
+        let t = b.transform(&Mat4::IDENTITY);
+        assert_eq!(t.axes, [Vec3::X, Vec3::Y, Vec3::Z]);
+        assert_eq!(t.half_extents, Vec3::new(0., 1., 1.));
+
+        let mut m = Mat4::from(Mat3::from_angle_z(FRAC_PI_4));
+        m.set_translation(Vec3::new(1., 2., 3.));
+        let t = b.transform(&m);
+        assert_eq!(t.half_extents, Vec3::new(0., 1., 1.));
+        let aabb = t.to_aabb();
+        let e = FRAC_PI_4.sin();
+        assert_approx_eq!(aabb.min, Pt3::new(1. - e, 2. - e, 2.));
+        assert_approx_eq!(aabb.max, Pt3::new(1. + e, 2. + e, 4.));
+
+        // A map flattening the box onto a line still gives a finite frame
+        let squash = Mat4::from(Mat3::from_diagonal(Vec3::new(1., 0., 0.)));
+        let t = Obb::from_aabb(&Aabb::new(Pt3::new(-1., -1., -1.), Pt3::new(1., 1., 1.)))
+            .transform(&squash);
+        assert!(approx_eq!(f32, t.rotation().determinant(), 1., PRECISION));
+        assert_eq!(t.half_extents, Vec3::new(1., 0., 0.));
+    }
+}
@@ -1,6 +1,23 @@
-use crate::{pt3::Pt3, structure::EuclideanSpace, vec3::Vec3};
+use crate::{
+    impl_conversions,
+    mat4::Mat4,
+    pt3::Pt3,
+    structure::{EuclideanSpace, SquareMat, VecSpace},
+    vec3::Vec3,
+    vec4::Vec4,
+};
+
+/// Position of a point relative to a plane, see `Plane::classify`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// Where the normal points.
+    Front,
+    Back,
+    On,
+}
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
 pub struct Plane {
     pub x: f32,
     pub y: f32,
@@ -13,19 +30,240 @@ impl Plane {
         Plane { x, y, z, d }
     }
 
+    /// Plane through `p` facing along `n`, which keeps its length.
+    pub fn from_point_normal(p: &Pt3, n: &Vec3) -> Plane {
+        Plane::new(n.x, n.y, n.z, -n.dot(&Vec3::from(p)))
+    }
+
+    /// Normalized plane through three points, facing the side from which
+    /// they wind counterclockwise. `None` when the points are collinear.
+    pub fn from_points(a: &Pt3, b: &Pt3, c: &Pt3) -> Option<Plane> {
+        let n = Vec3::from(b - a).cross(&Vec3::from(c - a));
+        let len = n.norm();
+        if len > 0. {
+            Some(Plane::from_point_normal(a, &(n / len)))
+        } else {
+            None
+        }
+    }
+
     pub fn normal(&self) -> Vec3 {
         Vec3::new(self.x, self.y, self.z)
     }
+
+    /// Same plane with a unit normal. A plane with a zero normal is returned
+    /// unchanged.
+    pub fn normalize(&self) -> Plane {
+        let n = self.normal().norm();
+        if n > 0. {
+            Plane::new(self.x / n, self.y / n, self.z / n, self.d / n)
+        } else {
+            *self
+        }
+    }
+
+    /// Same plane facing the other way.
+    pub fn flip(&self) -> Plane {
+        Plane::new(-self.x, -self.y, -self.z, -self.d)
+    }
+
+    /// Distance of `p` from the plane, positive in front of it. Unlike
+    /// `dot`, it does not need a unit normal. `None` when the normal is zero.
+    pub fn signed_distance(&self, p: &Pt3) -> Option<f32> {
+        let n = self.normal().norm();
+        (n > 0.).then(|| self.dot(p) / n)
+    }
+
+    /// Side of the plane `p` is on, `Side::On` within `tolerance` of it. A
+    /// plane with a zero normal puts every point on the side given by the
+    /// sign of `d`, like the far plane of an infinite frustum.
+    pub fn classify(&self, p: &Pt3, tolerance: f32) -> Side {
+        // Comparing against the scaled tolerance avoids dividing by the
+        // length of the normal
+        let d = self.dot(p);
+        let tolerance = tolerance * self.normal().norm();
+        if d > tolerance {
+            Side::Front
+        } else if d < -tolerance {
+            Side::Back
+        } else {
+            Side::On
+        }
+    }
+
+    pub fn closest_point(&self, p: &Pt3) -> Pt3 {
+        let n = self.normal();
+        p - n * (self.dot(p) / n.norm2())
+    }
+
+    /// Mirror image of `p`, as `Transform4::make_reflection` maps it.
+    pub fn reflect_point(&self, p: &Pt3) -> Pt3 {
+        let n = self.normal();
+        p - n * (2. * self.dot(p) / n.norm2())
+    }
+
+    /// Mirror image of a direction, which ignores the plane's offset.
+    pub fn reflect_vector(&self, v: &Vec3) -> Vec3 {
+        let n = self.normal();
+        v - n * (2. * n.dot(v) / n.norm2())
+    }
+
+    /// Image of the plane under `m`, which may be projective, scaled by
+    /// `|det(m)|`. `None` when the determinant is zero.
+    pub fn transform(&self, m: &Mat4) -> Option<Plane> {
+        // Planes are covectors, so they map by the inverse transpose. As they
+        // are homogeneous the cofactor matrix does the same without an
+        // inversion, the sign of the determinant keeps the facing
+        let det = m.determinant();
+        if det == 0. || !det.is_finite() {
+            return None;
+        }
+        let f = m.cofactor() * Vec4::new(self.x, self.y, self.z, self.d) * det.signum();
+        Some(Plane::new(f.x, f.y, f.z, f.w))
+    }
 }
 
 impl EuclideanSpace<Vec3> for Plane {
+    type Scalar = f32;
+
     fn dot(&self, v: &Vec3) -> f32 {
         self.x * v.x + self.y * v.y + self.z * v.z
     }
 }
 
 impl EuclideanSpace<Pt3> for Plane {
+    type Scalar = f32;
+
     fn dot(&self, p: &Pt3) -> f32 {
         self.x * p.x + self.y * p.y + self.z * p.z + self.d
     }
 }
+
+impl_conversions!(Plane => [f32; 4], |f: &Plane| {
+    [f.x, f.y, f.z, f.d]
+});
+
+impl_conversions!([f32; 4] => Plane, |a: &[f32; 4]| {
+    Plane::new(a[0], a[1], a[2], a[3])
+});
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use crate::{
+        assert_approx_eq, mat3::Mat3, precision::PRECISION, structure::Mat, transform4::Transform4,
+    };
+
+    use super::*;
+
+    #[test]
+    fn constructs() {
+        let p = Pt3::new(1., 2., 3.);
+        let f = Plane::from_point_normal(&p, &Vec3::new(0., 2., 0.));
+        assert!(approx_eq!(f32, f.dot(&p), 0., PRECISION));
+        assert_eq!(f.normalize().normal(), Vec3::Y);
+        assert!(approx_eq!(f32, f.normalize().d, -2., PRECISION));
+
+        let g = Plane::from_points(
+            &Pt3::new(0., 0., 1.),
+            &Pt3::new(1., 0., 1.),
+            &Pt3::new(0., 1., 1.),
+        )
+        .unwrap();
+        assert_eq!(g.normal(), Vec3::Z);
+        assert!(approx_eq!(f32, g.d, -1., PRECISION));
+        assert!(Plane::from_points(&p, &(p + Vec3::X), &(p + Vec3::X * 2.)).is_none());
+        assert_eq!(Plane::new(0., 0., 0., 1.).normalize().d, 1.);
+    }
+
+    #[test]
+    fn measures_and_projects() {
+        // y = 2 with an unnormalized normal
+        let f = Plane::new(0., 3., 0., -6.);
+        let p = Pt3::new(1., 5., -1.);
+        assert!(approx_eq!(
+            f32,
+            f.signed_distance(&p).unwrap(),
+            3.,
+            PRECISION
+        ));
+        assert!(approx_eq!(
+            f32,
+            f.flip().signed_distance(&p).unwrap(),
+            -3.,
+            PRECISION
+        ));
+        assert_approx_eq!(f.closest_point(&p), Pt3::new(1., 2., -1.));
+
+        assert_eq!(f.classify(&p, 0.1), Side::Front);
+        assert_eq!(f.classify(&Pt3::new(0., 1., 0.), 0.1), Side::Back);
+        assert_eq!(f.classify(&Pt3::new(0., 2.05, 0.), 0.1), Side::On);
+
+        // A zero normal has no distance and no plane to be on
+        let degenerate = Plane::new(0., 0., 0., 1.);
+        assert!(degenerate.signed_distance(&p).is_none());
+        assert_eq!(degenerate.classify(&p, 0.1), Side::Front);
+        assert_eq!(degenerate.flip().classify(&p, 0.1), Side::Back);
+    }
+
+    #[test]
+    fn reflects_like_its_matrix() {
+        let f = Plane::new(1., 1., 0., -2.).normalize();
+        let m = Mat4::make_reflection(&f);
+        let p = Pt3::new(3., -1., 4.);
+        let mp = m * Vec3::from(p).extend(1.);
+        assert_approx_eq!(f.reflect_point(&p), Pt3::from(mp.xyz()));
+        assert_approx_eq!(f.reflect_point(&f.reflect_point(&p)), p);
+
+        let v = Vec3::new(1., 0., 2.);
+        assert_eq!(f.reflect_vector(&v), (m * v.extend(0.)).xyz());
+    }
+
+    #[test]
+    fn transforms() {
+        let mut m =
+            Mat4::from(Mat3::from_angle_z(0.7) * Mat3::from_diagonal(Vec3::new(2., 1., 3.)));
+        m.set_translation(Vec3::new(1., -2., 5.));
+        let f = Plane::new(1., 2., -1., 3.);
+        let g = f.transform(&m).unwrap();
+        // Points on f map to points on g
+        for p in [
+            Pt3::new(-3., 0., 0.),
+            Pt3::new(0., 0., 3.),
+            Pt3::new(1., -1., 2.),
+        ] {
+            assert!(approx_eq!(f32, f.dot(&p), 0., PRECISION));
+            let q = m * Vec3::from(p).extend(1.);
+            assert!(g.dot(&Pt3::from(q.xyz())).abs() < 1e-4);
+        }
+
+        // A projective map keeps the plane as long as w is divided out
+        let proj = Mat4::perspective_rh(1., 1., 0.1, 10.);
+        let h = f.transform(&proj).unwrap();
+        let q = proj * Vec3::new(1., -1., 2.).extend(1.);
+        assert!(approx_eq!(
+            f32,
+            h.dot(&Pt3::from(q.xyz() / q.w)),
+            0.,
+            epsilon = 1e-4
+        ));
+        assert!(Plane::new(0., 0., 0., 0.)
+            .transform(&Mat4::from(Mat3::ZERO))
+            .is_none());
+
+        // A small model matrix far from the origin
+        let mut m = Mat4::from(Mat3::from_diagonal(Vec3::new(0.01, 0.01, 0.01)));
+        m.set_translation(Vec3::new(1000., 1000., 1000.));
+        let g = f.transform(&m).unwrap();
+        let q = m * Vec3::new(1., -1., 2.).extend(1.);
+        assert!(g.signed_distance(&Pt3::from(q.xyz())).unwrap().abs() < 1e-3);
+        let front = m * Vec3::new(1., 0., 0.).extend(1.);
+        assert_eq!(g.classify(&Pt3::from(front.xyz()), 0.), Side::Front);
+
+        // A mirror keeps the facing of the mapped points
+        let mirror = Mat4::from(Mat3::from_diagonal(Vec3::new(-1., 1., 1.)));
+        let g = f.transform(&mirror).unwrap();
+        assert_eq!(g.classify(&Pt3::new(-1., 0., 0.), 0.), Side::Front);
+    }
+}
@@ -1,12 +1,53 @@
-use float_cmp::{ApproxEq, F32Margin};
+use float_cmp::{ApproxEq, F32Margin, F64Margin};
 
-use crate::{mat3::Mat3, mat4::Mat4, quat::Quat, vec3::Vec3, vec4::Vec4};
+use crate::scalar::Scalar;
 
+use crate::{
+    affine3::Affine3, dmat3::DMat3, dmat4::DMat4, dpt3::DPt3, dquat::DQuat, dual_quat::DualQuat,
+    dvec3::DVec3, dvec4::DVec4, mat2::Mat2, mat3::Mat3, mat4::Mat4, quat::Quat, vec2::Vec2,
+    vec3::Vec3, vec4::Vec4,
+};
+
+/// Margin of the `PartialEq` impls below, which makes `==` tolerant but not
+/// transitive. `ApproxEq` picks the margin per comparison and `Exact`
+/// compares bitwise.
 pub const PRECISION: F32Margin = F32Margin {
     ulps: 2,
     epsilon: 1e-3,
 };
 
+pub const PRECISION_F64: F64Margin = F64Margin {
+    ulps: 2,
+    epsilon: 1e-9,
+};
+
+/// Smallest ratio of `|det(M)|` to the product of the column norms of `M`
+/// that `SquareMat::invert` accepts. The ratio is 1 for orthogonal matrices
+/// and tends to 0 as the columns become linearly dependent, so unlike the
+/// raw determinant it does not depend on the overall scale of the matrix.
+/// The translation column of an affine `Mat4` counts as a unit column.
+pub const SINGULAR_EPSILON: f32 = 1e-6;
+
+pub(crate) fn is_invertible<S: Scalar>(det: S, column_norms: &[S]) -> bool {
+    let scale = column_norms.iter().fold(S::ONE, |acc, n| acc * *n);
+    det.is_finite() && det.abs() > S::SINGULAR_EPSILON * scale
+}
+
+impl ApproxEq for &Vec2 {
+    type Margin = F32Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.x.approx_eq(other.x, margin) && self.y.approx_eq(other.y, margin)
+    }
+}
+
+impl PartialEq for Vec2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION)
+    }
+}
+
 impl ApproxEq for &Vec3 {
     type Margin = F32Margin;
 
@@ -42,6 +83,21 @@ impl PartialEq for Vec4 {
     }
 }
 
+impl ApproxEq for &Mat2 {
+    type Margin = F32Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.a.approx_eq(&other.a, margin) && self.b.approx_eq(&other.b, margin)
+    }
+}
+
+impl PartialEq for Mat2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION)
+    }
+}
+
 impl ApproxEq for &Mat3 {
     type Margin = F32Margin;
 
@@ -77,6 +133,23 @@ impl PartialEq for Mat4 {
     }
 }
 
+impl ApproxEq for &Affine3 {
+    type Margin = F32Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.a.approx_eq(&other.a, margin)
+            && self.b.approx_eq(&other.b, margin)
+            && self.c.approx_eq(&other.c, margin)
+    }
+}
+
+impl PartialEq for Affine3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION)
+    }
+}
+
 impl ApproxEq for &Quat {
     type Margin = F32Margin;
 
@@ -94,3 +167,123 @@ impl PartialEq for Quat {
         self.approx_eq(other, PRECISION)
     }
 }
+
+impl ApproxEq for &DualQuat {
+    type Margin = F32Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.real.approx_eq(&other.real, margin) && self.dual.approx_eq(&other.dual, margin)
+    }
+}
+
+impl PartialEq for DualQuat {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION)
+    }
+}
+
+impl ApproxEq for &DVec3 {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.x.approx_eq(other.x, margin)
+            && self.y.approx_eq(other.y, margin)
+            && self.z.approx_eq(other.z, margin)
+    }
+}
+
+impl PartialEq for DVec3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION_F64)
+    }
+}
+
+impl ApproxEq for &DVec4 {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.x.approx_eq(other.x, margin)
+            && self.y.approx_eq(other.y, margin)
+            && self.z.approx_eq(other.z, margin)
+            && self.w.approx_eq(other.w, margin)
+    }
+}
+
+impl PartialEq for DVec4 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION_F64)
+    }
+}
+
+impl ApproxEq for &DPt3 {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.x.approx_eq(other.x, margin)
+            && self.y.approx_eq(other.y, margin)
+            && self.z.approx_eq(other.z, margin)
+    }
+}
+
+impl PartialEq for DPt3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION_F64)
+    }
+}
+
+impl ApproxEq for &DMat3 {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.a.approx_eq(&other.a, margin)
+            && self.b.approx_eq(&other.b, margin)
+            && self.c.approx_eq(&other.c, margin)
+    }
+}
+
+impl PartialEq for DMat3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION_F64)
+    }
+}
+
+impl ApproxEq for &DMat4 {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.a.approx_eq(&other.a, margin)
+            && self.b.approx_eq(&other.b, margin)
+            && self.c.approx_eq(&other.c, margin)
+            && self.d.approx_eq(&other.d, margin)
+    }
+}
+
+impl PartialEq for DMat4 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION_F64)
+    }
+}
+
+impl ApproxEq for &DQuat {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+        self.x.approx_eq(other.x, margin)
+            && self.y.approx_eq(other.y, margin)
+            && self.z.approx_eq(other.z, margin)
+            && self.w.approx_eq(other.w, margin)
+    }
+}
+
+impl PartialEq for DQuat {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, PRECISION_F64)
+    }
+}
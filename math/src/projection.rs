@@ -0,0 +1,321 @@
+use crate::mat4::Mat4;
+
+// All projections target Vulkan clip space: x right, y down and depth in
+// [0, 1] (or [1, 0] for reverse-Z). `_rh` constructors expect a
+// right-handed, y-up view space looking down -z, `_lh` constructors a
+// left-handed, y-up view space looking down +z. Matrices are laid out in
+// rows so that `projection * view_pos.extend(1.)` yields clip coordinates.
+
+const RH: f32 = -1.;
+const LH: f32 = 1.;
+
+fn perspective(s: f32, x_scale: f32, y_scale: f32, near: f32, far: f32) -> Mat4 {
+    let depth = far / (far - near);
+    [
+        [x_scale, 0., 0., 0.],
+        [0., -y_scale, 0., 0.],
+        [0., 0., s * depth, -near * depth],
+        [0., 0., s, 0.],
+    ]
+    .into()
+}
+
+fn perspective_infinite(s: f32, x_scale: f32, y_scale: f32, near: f32) -> Mat4 {
+    [
+        [x_scale, 0., 0., 0.],
+        [0., -y_scale, 0., 0.],
+        [0., 0., s, -near],
+        [0., 0., s, 0.],
+    ]
+    .into()
+}
+
+fn perspective_reverse_z(s: f32, x_scale: f32, y_scale: f32, near: f32, far: f32) -> Mat4 {
+    let depth = near / (far - near);
+    [
+        [x_scale, 0., 0., 0.],
+        [0., -y_scale, 0., 0.],
+        [0., 0., -s * depth, far * depth],
+        [0., 0., s, 0.],
+    ]
+    .into()
+}
+
+fn perspective_infinite_reverse_z(s: f32, x_scale: f32, y_scale: f32, near: f32) -> Mat4 {
+    [
+        [x_scale, 0., 0., 0.],
+        [0., -y_scale, 0., 0.],
+        [0., 0., 0., near],
+        [0., 0., s, 0.],
+    ]
+    .into()
+}
+
+fn frustum(s: f32, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+    let w = 1. / (right - left);
+    let h = 1. / (top - bottom);
+    let depth = far / (far - near);
+    [
+        [2. * near * w, 0., -s * (right + left) * w, 0.],
+        [0., -2. * near * h, s * (top + bottom) * h, 0.],
+        [0., 0., s * depth, -near * depth],
+        [0., 0., s, 0.],
+    ]
+    .into()
+}
+
+fn orthographic(s: f32, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+    let w = 1. / (right - left);
+    let h = 1. / (top - bottom);
+    let d = 1. / (far - near);
+    [
+        [2. * w, 0., 0., -(right + left) * w],
+        [0., -2. * h, 0., (top + bottom) * h],
+        [0., 0., s * d, -near * d],
+        [0., 0., 0., 1.],
+    ]
+    .into()
+}
+
+fn focal_lengths(fov_y: f32, aspect: f32) -> (f32, f32) {
+    let g = 1. / (fov_y * 0.5).tan();
+    (g / aspect, g)
+}
+
+impl Mat4 {
+    pub fn perspective_rh(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective(RH, x_scale, y_scale, near, far)
+    }
+
+    pub fn perspective_lh(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective(LH, x_scale, y_scale, near, far)
+    }
+
+    pub fn perspective_infinite_rh(fov_y: f32, aspect: f32, near: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective_infinite(RH, x_scale, y_scale, near)
+    }
+
+    pub fn perspective_infinite_lh(fov_y: f32, aspect: f32, near: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective_infinite(LH, x_scale, y_scale, near)
+    }
+
+    /// Maps the near plane to depth 1 and the far plane to depth 0, which
+    /// spreads float precision evenly over the depth range when paired with
+    /// a `GREATER` depth test.
+    pub fn perspective_reverse_z_rh(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective_reverse_z(RH, x_scale, y_scale, near, far)
+    }
+
+    pub fn perspective_reverse_z_lh(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective_reverse_z(LH, x_scale, y_scale, near, far)
+    }
+
+    pub fn perspective_infinite_reverse_z_rh(fov_y: f32, aspect: f32, near: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective_infinite_reverse_z(RH, x_scale, y_scale, near)
+    }
+
+    pub fn perspective_infinite_reverse_z_lh(fov_y: f32, aspect: f32, near: f32) -> Self {
+        let (x_scale, y_scale) = focal_lengths(fov_y, aspect);
+        perspective_infinite_reverse_z(LH, x_scale, y_scale, near)
+    }
+
+    /// Off-center perspective projection. `left`, `right`, `bottom` and
+    /// `top` are the extents of the view volume on the near plane.
+    pub fn frustum_rh(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        frustum(RH, left, right, bottom, top, near, far)
+    }
+
+    pub fn frustum_lh(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        frustum(LH, left, right, bottom, top, near, far)
+    }
+
+    pub fn orthographic_rh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        orthographic(RH, left, right, bottom, top, near, far)
+    }
+
+    pub fn orthographic_lh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        orthographic(LH, left, right, bottom, top, near, far)
+    }
+
+    /// Inverts any matrix built by the `perspective_*` and `frustum_*`
+    /// constructors in closed form, without the cancellation a general
+    /// 4x4 inverse suffers on their sparse layout.
+    pub fn invert_perspective(&self) -> Self {
+        // Warning: only the entries a perspective projection can populate
+        // are read, anything else is assumed to be zero
+        let m = self;
+        let (p, c) = (m[0][0], m[0][2]);
+        let (q, d) = (m[1][1], m[1][2]);
+        let (a, b) = (m[2][2], m[2][3]);
+        let e = m[3][2];
+        [
+            [1. / p, 0., 0., -c / (p * e)],
+            [0., 1. / q, 0., -d / (q * e)],
+            [0., 0., 0., 1. / e],
+            [0., 0., 1. / b, -a / (b * e)],
+        ]
+        .into()
+    }
+
+    /// Inverts any matrix built by the `orthographic_*` constructors in
+    /// closed form.
+    pub fn invert_orthographic(&self) -> Self {
+        // Warning: only the diagonal and the translation are read
+        let m = self;
+        let (p, q, r) = (1. / m[0][0], 1. / m[1][1], 1. / m[2][2]);
+        [
+            [p, 0., 0., -m[0][3] * p],
+            [0., q, 0., -m[1][3] * q],
+            [0., 0., r, -m[2][3] * r],
+            [0., 0., 0., 1.],
+        ]
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::{structure::SquareMat, vec3::Vec3, vec4::Vec4};
+
+    use super::*;
+
+    fn to_ndc(m: &Mat4, p: Vec3) -> Vec3 {
+        let clip = m * p.extend(1.);
+        clip.xyz() / clip.w
+    }
+
+    #[test]
+    fn perspective_rh_maps_frustum_corners() {
+        let m = Mat4::perspective_rh(FRAC_PI_2, 2., 1., 10.);
+        // With a 90 degree vertical fov the half-height at depth z is z
+        assert_eq!(to_ndc(&m, Vec3::new(-2., 1., -1.)), Vec3::new(-1., -1., 0.));
+        assert_eq!(to_ndc(&m, Vec3::new(2., -1., -1.)), Vec3::new(1., 1., 0.));
+        assert_eq!(
+            to_ndc(&m, Vec3::new(-20., 10., -10.)),
+            Vec3::new(-1., -1., 1.)
+        );
+        assert_eq!(
+            to_ndc(&m, Vec3::new(20., -10., -10.)),
+            Vec3::new(1., 1., 1.)
+        );
+    }
+
+    #[test]
+    fn perspective_lh_maps_frustum_corners() {
+        let m = Mat4::perspective_lh(FRAC_PI_2, 2., 1., 10.);
+        assert_eq!(to_ndc(&m, Vec3::new(-2., 1., 1.)), Vec3::new(-1., -1., 0.));
+        assert_eq!(to_ndc(&m, Vec3::new(20., -10., 10.)), Vec3::new(1., 1., 1.));
+    }
+
+    #[test]
+    fn perspective_infinite_approaches_far_depth() {
+        let m = Mat4::perspective_infinite_rh(FRAC_PI_2, 1., 1.);
+        assert_eq!(to_ndc(&m, Vec3::new(1., 1., -1.)), Vec3::new(1., -1., 0.));
+        let far = to_ndc(&m, Vec3::new(0., 0., -1e6));
+        assert!(far.z < 1. && far.z > 0.999);
+
+        let m = Mat4::perspective_infinite_lh(FRAC_PI_2, 1., 1.);
+        assert_eq!(to_ndc(&m, Vec3::new(1., 1., 1.)), Vec3::new(1., -1., 0.));
+    }
+
+    #[test]
+    fn perspective_reverse_z_swaps_depth() {
+        let m = Mat4::perspective_reverse_z_rh(FRAC_PI_2, 1., 1., 10.);
+        assert_eq!(to_ndc(&m, Vec3::new(0., 0., -1.)).z, 1.);
+        assert_eq!(to_ndc(&m, Vec3::new(0., 0., -10.)).z, 0.);
+
+        let m = Mat4::perspective_reverse_z_lh(FRAC_PI_2, 1., 1., 10.);
+        assert_eq!(to_ndc(&m, Vec3::new(0., 0., 1.)).z, 1.);
+        assert_eq!(to_ndc(&m, Vec3::new(0., 0., 10.)).z, 0.);
+
+        let m = Mat4::perspective_infinite_reverse_z_rh(FRAC_PI_2, 1., 1.);
+        assert_eq!(to_ndc(&m, Vec3::new(-1., -1., -1.)), Vec3::new(-1., 1., 1.));
+        assert!(to_ndc(&m, Vec3::new(0., 0., -1e6)).z < 1e-5);
+    }
+
+    #[test]
+    fn frustum_maps_off_center_corners() {
+        let m = Mat4::frustum_rh(-1., 3., -2., 1., 1., 5.);
+        assert_eq!(to_ndc(&m, Vec3::new(-1., 1., -1.)), Vec3::new(-1., -1., 0.));
+        assert_eq!(to_ndc(&m, Vec3::new(3., -2., -1.)), Vec3::new(1., 1., 0.));
+        assert_eq!(to_ndc(&m, Vec3::new(15., -10., -5.)), Vec3::new(1., 1., 1.));
+
+        let m = Mat4::frustum_lh(-1., 3., -2., 1., 1., 5.);
+        assert_eq!(to_ndc(&m, Vec3::new(-1., 1., 1.)), Vec3::new(-1., -1., 0.));
+        assert_eq!(to_ndc(&m, Vec3::new(15., -10., 5.)), Vec3::new(1., 1., 1.));
+    }
+
+    #[test]
+    fn frustum_matches_symmetric_perspective() {
+        assert_eq!(
+            Mat4::frustum_rh(-2., 2., -1., 1., 1., 10.),
+            Mat4::perspective_rh(FRAC_PI_2, 2., 1., 10.)
+        );
+    }
+
+    #[test]
+    fn orthographic_maps_box_corners() {
+        let m = Mat4::orthographic_rh(-4., 4., -2., 2., 0.5, 8.);
+        assert_eq!(
+            to_ndc(&m, Vec3::new(-4., 2., -0.5)),
+            Vec3::new(-1., -1., 0.)
+        );
+        assert_eq!(to_ndc(&m, Vec3::new(4., -2., -8.)), Vec3::new(1., 1., 1.));
+
+        let m = Mat4::orthographic_lh(0., 8., 0., 4., 0., 8.);
+        assert_eq!(to_ndc(&m, Vec3::new(0., 4., 0.)), Vec3::new(-1., -1., 0.));
+        assert_eq!(to_ndc(&m, Vec3::new(8., 0., 8.)), Vec3::new(1., 1., 1.));
+    }
+
+    #[test]
+    fn inverts_perspective() {
+        let projections = [
+            Mat4::perspective_rh(1.2, 1.5, 0.1, 100.),
+            Mat4::perspective_lh(1.2, 1.5, 0.1, 100.),
+            Mat4::perspective_infinite_rh(1.2, 1.5, 0.1),
+            Mat4::perspective_reverse_z_lh(1.2, 1.5, 0.1, 100.),
+            Mat4::perspective_infinite_reverse_z_rh(1.2, 1.5, 0.1),
+            Mat4::frustum_rh(-1., 3., -2., 1., 1., 5.),
+        ];
+        for m in projections {
+            assert_eq!(m * m.invert_perspective(), Mat4::IDENTITY);
+            assert_eq!(m.invert_perspective(), m.invert().unwrap());
+        }
+    }
+
+    #[test]
+    fn inverts_orthographic() {
+        let m = Mat4::orthographic_rh(-4., 4., -2., 2., 0.5, 8.);
+        assert_eq!(m * m.invert_orthographic(), Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn unprojects_ndc_corner() {
+        let m = Mat4::perspective_rh(FRAC_PI_2, 2., 1., 10.);
+        let p = m.invert_perspective() * Vec4::new(1., 1., 1., 1.);
+        assert_eq!(p.xyz() / p.w, Vec3::new(20., -10., -10.));
+    }
+}
@@ -1,8 +1,33 @@
-use crate::{impl_algebraic_ops, impl_conversions, impl_index_ops, pt3::Pt3, vec3::Vec3};
+use crate::{
+    dpt3::DPt3, dvec3::DVec3, impl_algebraic_ops, impl_componentwise_ops, impl_conversions,
+    impl_index_ops, pt2::Pt2, pt3::Pt3, vec2::Vec2, vec3::Vec3,
+};
 use std::ops;
 
+impl_algebraic_ops!(Pt2 { x, y }, 2);
+impl_algebraic_ops!(Pt2 : Vec2 { x, y }, 2);
 impl_algebraic_ops!(Pt3 { x, y, z }, 3);
 impl_algebraic_ops!(Pt3 : Vec3 { x, y, z }, 3);
+impl_algebraic_ops!(DPt3 { x, y, z }, 3, f64);
+impl_algebraic_ops!(DPt3 : DVec3 { x, y, z }, 3);
+
+impl_componentwise_ops!(Pt3 { x, y, z }, 3);
+
+impl_conversions!(Pt2 => [f32; 2], |p: &Pt2| {
+    [p.x, p.y]
+});
+
+impl_conversions!([f32; 2] => Pt2, |a: &[f32; 2]| {
+    Pt2::new(a[0], a[1])
+});
+
+impl_conversions!(Pt2 => Vec2, |p: &Pt2| {
+    Vec2::new(p.x, p.y)
+});
+
+impl_conversions!(Vec2 => Pt2, |v: &Vec2| {
+    Pt2::new(v.x, v.y)
+});
 
 impl_conversions!(Pt3 => [f32; 3], |p: &Pt3| {
     [p.x, p.y, p.z]
@@ -20,4 +45,26 @@ impl_conversions!(Vec3 => Pt3, |v: &Vec3| {
     Pt3::new(v.x, v.y, v.z)
 });
 
+impl_conversions!(DPt3 => [f64; 3], |p: &DPt3| {
+    [p.x, p.y, p.z]
+});
+
+impl_conversions!([f64; 3] => DPt3, |a: &[f64; 3]| {
+    DPt3::new(a[0], a[1], a[2])
+});
+
+impl_conversions!(DPt3 => DVec3, |p: &DPt3| {
+    DVec3::new(p.x, p.y, p.z)
+});
+
+impl_conversions!(DVec3 => DPt3, |v: &DVec3| {
+    DPt3::new(v.x, v.y, v.z)
+});
+
+impl_conversions!(Pt3 => DPt3, |p: &Pt3| {
+    DPt3::new(p.x.into(), p.y.into(), p.z.into())
+});
+
+impl_index_ops!(Pt2 { 0 => x, 1 => y } => f32);
 impl_index_ops!(Pt3 { 0 => x, 1 => y, 2 => z } => f32);
+impl_index_ops!(DPt3 { 0 => x, 1 => y, 2 => z } => f64);
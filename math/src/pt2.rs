@@ -0,0 +1,24 @@
+use crate::structure::{EuclideanSpace, VecSpace};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct Pt2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Pt2 {
+    pub fn new(x: f32, y: f32) -> Pt2 {
+        Pt2 { x, y }
+    }
+}
+
+impl VecSpace for Pt2 {}
+
+impl EuclideanSpace<Pt2> for Pt2 {
+    type Scalar = f32;
+
+    fn dot(&self, other: &Pt2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+}
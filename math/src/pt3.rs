@@ -1,6 +1,7 @@
 use crate::structure::{EuclideanSpace, VecSpace};
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
 pub struct Pt3 {
     pub x: f32,
     pub y: f32,
@@ -16,6 +17,8 @@ impl Pt3 {
 impl VecSpace for Pt3 {}
 
 impl EuclideanSpace<Pt3> for Pt3 {
+    type Scalar = f32;
+
     fn dot(&self, other: &Pt3) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
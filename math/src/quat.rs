@@ -1,9 +1,16 @@
-use crate::{impl_conversions, mat3::Mat3, structure::EuclideanSpace};
+use crate::{
+    impl_algebraic_ops, impl_conversions, impl_index_ops,
+    mat3::Mat3,
+    simd,
+    structure::{EuclideanSpace, Mat, VecSpace},
+};
 use std::ops;
 
 use crate::{impl_op, vec3::Vec3};
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(all(feature = "gpu", not(feature = "simd")), repr(C))]
+#[cfg_attr(feature = "simd", repr(C, align(16)))]
 pub struct Quat {
     pub x: f32,
     pub y: f32,
@@ -12,15 +19,142 @@ pub struct Quat {
 }
 
 impl Quat {
+    pub const ZERO: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+        w: 0.,
+    };
+
+    pub const IDENTITY: Self = Self {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+        w: 1.,
+    };
+
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quat {
         Quat { x, y, z, w }
     }
 
+    pub fn from_vec_part(v: Vec3, w: f32) -> Quat {
+        Quat::new(v.x, v.y, v.z, w)
+    }
+
     pub fn get_vec_part(&self) -> Vec3 {
         Vec3::new(self.x, self.y, self.z)
     }
+
+    pub fn from_axis_angle(a: Vec3, t: f32) -> Quat {
+        // Warning: `a` has to be normalized
+        let (s, c) = (t * 0.5).sin_cos();
+        Quat::from_vec_part(a * s, c)
+    }
+
+    /// Returns a unit axis and an angle in `[0, 2pi]`. The identity rotation
+    /// yields the x axis and a zero angle.
+    pub fn to_axis_angle(&self) -> (Vec3, f32) {
+        // Warning: `self` has to be normalized
+        let v = self.get_vec_part();
+        let s = v.norm();
+        if s > f32::EPSILON {
+            (v / s, 2. * s.atan2(self.w))
+        } else {
+            (Vec3::X, 0.)
+        }
+    }
+
+    /// Shortest rotation taking the direction of `a` onto the direction of
+    /// `b`.
+    pub fn from_rotation_arc(a: Vec3, b: Vec3) -> Quat {
+        let a = a.normalize();
+        let b = b.normalize();
+        let c = a.dot(&b);
+        if c < -1. + 1e-6 {
+            // Antiparallel: any axis perpendicular to `a` works, pick the one
+            // least aligned with `a` to stay well conditioned
+            let other = if a.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
+            let axis = a.cross(&other).normalize();
+            Quat::from_vec_part(axis, 0.)
+        } else {
+            // Half-angle trick: (a x b, 1 + a.b) normalized is the rotation
+            Quat::from_vec_part(a.cross(&b), 1. + c).normalize()
+        }
+    }
+
+    /// Rotation taking the z axis onto `forward` and the y axis as close to
+    /// `up` as possible.
+    pub fn look_rotation(forward: Vec3, up: Vec3) -> Quat {
+        // Warning: `forward` must not be parallel to `up`
+        let z = forward.normalize();
+        let x = up.cross(&z).normalize();
+        let y = z.cross(&x);
+        Mat3::from_columns(x, y, z).transpose().into()
+    }
+
+    pub fn conjugate(&self) -> Quat {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(&self) -> Quat {
+        self.conjugate() / self.norm2()
+    }
+
+    pub fn rotate(&self, v: &Vec3) -> Vec3 {
+        v * self
+    }
+
+    /// Normalized linear interpolation along the shorter arc. Cheaper than
+    /// `slerp` but does not move at constant angular velocity.
+    pub fn nlerp(&self, other: &Quat, t: f32) -> Quat {
+        let other = if self.dot(other) < 0. { -other } else { *other };
+        (self * (1. - t) + other * t).normalize()
+    }
+
+    /// Spherical linear interpolation along the shorter arc.
+    pub fn slerp(&self, other: &Quat, t: f32) -> Quat {
+        // Warning: both quaternions have to be normalized
+        let mut c = self.dot(other);
+        let mut other = *other;
+        if c < 0. {
+            c = -c;
+            other = -other;
+        }
+        if c > 1. - 1e-6 {
+            // Nearly identical rotations, sin(theta) would vanish
+            return self.nlerp(&other, t);
+        }
+        let theta = c.acos();
+        let inv_sin = 1. / theta.sin();
+        let a = ((1. - t) * theta).sin() * inv_sin;
+        let b = (t * theta).sin() * inv_sin;
+        self * a + other * b
+    }
+
+    /// Angle in `[0, pi]` of the rotation taking `self` to `other`.
+    pub fn angle_between(&self, other: &Quat) -> f32 {
+        // Warning: both quaternions have to be normalized
+        2. * self.dot(other).abs().min(1.).acos()
+    }
+}
+
+impl EuclideanSpace<Self> for Quat {
+    type Scalar = f32;
+
+    fn dot(&self, other: &Self) -> f32 {
+        if cfg!(feature = "simd") {
+            return simd::quat_dot(self, other);
+        }
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
 }
 
+impl VecSpace for Quat {}
+
+impl_algebraic_ops!(Quat { x, y, z, w }, 4);
+
+impl_index_ops!(Quat { 0 => x, 1 => y, 2 => z, 3 => w } => f32);
+
 impl_conversions!(Quat => [f32; 4], |from: &Quat| {
     [from.x, from.y, from.z, from.w]
 });
@@ -30,6 +164,9 @@ impl_conversions!([f32; 4] => Quat, |from: &[f32; 4]| {
 });
 
 impl_op!(Quat : Quat, ops::Mul { fn mul |lhs: &Quat, rhs: &Quat| {
+    if cfg!(feature = "simd") {
+        return simd::quat_mul(lhs, rhs);
+    }
     let [x1, y1, z1, w1]: [f32; 4] = lhs.into();
     let [x2, y2, z2, w2]: [f32; 4] = rhs.into();
     Quat::new(
@@ -60,7 +197,7 @@ impl_conversions!(Quat => Mat3, |q: &Quat| {
     Mat3::new(
         1. - 2. * (y2 + z2), 2. * (xy - wz), 2. * (xz + wy),
         2. * (xy + wz), 1. - 2. * (x2 + z2), 2. * (yz - wx),
-        2. * (xz - xy), 2. * (yz + wx), 1. - 2. * (x2 + y2)
+        2. * (xz - wy), 2. * (yz + wx), 1. - 2. * (x2 + y2)
     )
 });
 
@@ -103,3 +240,177 @@ impl_conversions!(Mat3 => Quat, |m: &Mat3| {
         Quat::new(x, y, z, w)
     }
 });
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use float_cmp::approx_eq;
+
+    use crate::precision::PRECISION;
+
+    use super::*;
+
+    #[test]
+    fn identity_does_not_rotate() {
+        let v = Vec3::new(1., 2., 3.);
+        assert_eq!(Quat::IDENTITY.rotate(&v), v);
+        assert_eq!(
+            Mat3::from(Quat::IDENTITY),
+            [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]].into()
+        );
+    }
+
+    #[test]
+    fn rotates_about_axis() {
+        let q = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        assert_eq!(q.rotate(&Vec3::X), Vec3::Y);
+        assert_eq!(Vec3::Y * q, -Vec3::X);
+    }
+
+    #[test]
+    fn matches_mat3_axis_angle() {
+        let a = Vec3::new(1., 2., 3.).normalize();
+        let q = Quat::from_axis_angle(a, 0.5);
+        assert_eq!(Mat3::from(q), Mat3::from_axis_angle(a, 0.5));
+        let v = Vec3::new(-2., 0.5, 4.);
+        assert_eq!(Mat3::from(q) * v, q.rotate(&v));
+    }
+
+    #[test]
+    fn round_trips_mat3() {
+        let q = Quat::from_axis_angle(Vec3::new(-1., 4., 2.).normalize(), 2.5);
+        let back = Quat::from(Mat3::from(q));
+        assert!(back == q || back == -q);
+    }
+
+    #[test]
+    fn round_trips_axis_angle() {
+        let a = Vec3::new(1., -2., 2.).normalize();
+        let (axis, angle) = Quat::from_axis_angle(a, 1.2).to_axis_angle();
+        assert_eq!(axis, a);
+        assert!(approx_eq!(f32, angle, 1.2, PRECISION));
+
+        let (axis, angle) = Quat::IDENTITY.to_axis_angle();
+        assert_eq!(axis, Vec3::X);
+        assert_eq!(angle, 0.);
+    }
+
+    #[test]
+    fn conjugates_and_inverts() {
+        let q = Quat::new(1., 2., 3., 4.);
+        assert_eq!(q.conjugate(), Quat::new(-1., -2., -3., 4.));
+        assert_eq!(q * q.inverse(), Quat::IDENTITY);
+        assert_eq!(q.inverse() * q, Quat::IDENTITY);
+    }
+
+    #[test]
+    fn normalizes() {
+        let q = Quat::new(1., 2., 3., 4.).normalize();
+        assert!(approx_eq!(f32, q.norm(), 1., PRECISION));
+    }
+
+    #[test]
+    fn composes_rotations() {
+        let qx = Quat::from_axis_angle(Vec3::X, 0.3);
+        let qy = Quat::from_axis_angle(Vec3::Y, -1.1);
+        let v = Vec3::new(1., 2., 3.);
+        assert_eq!((qy * qx).rotate(&v), qy.rotate(&qx.rotate(&v)));
+    }
+
+    #[test]
+    fn rotation_arc_maps_directions() {
+        let a = Vec3::new(1., 2., 3.);
+        let b = Vec3::new(-3., 0.5, 1.);
+        let q = Quat::from_rotation_arc(a, b);
+        assert_eq!(q.rotate(&a.normalize()), b.normalize());
+
+        let q = Quat::from_rotation_arc(Vec3::X, -Vec3::X);
+        assert_eq!(q.rotate(&Vec3::X), -Vec3::X);
+        assert!(approx_eq!(f32, q.norm(), 1., PRECISION));
+
+        assert_eq!(Quat::from_rotation_arc(Vec3::Y, Vec3::Y), Quat::IDENTITY);
+    }
+
+    #[test]
+    fn look_rotation_aligns_axes() {
+        let forward = Vec3::new(1., 0., 1.);
+        let q = Quat::look_rotation(forward, Vec3::Y);
+        assert_eq!(q.rotate(&Vec3::Z), forward.normalize());
+        assert_eq!(q.rotate(&Vec3::Y), Vec3::Y);
+    }
+
+    #[test]
+    fn dot_works() {
+        assert_eq!(
+            Quat::new(1., 2., 3., 4.).dot(&Quat::new(5., 6., 7., 8.)),
+            70.
+        );
+    }
+
+    #[test]
+    fn slerps() {
+        let a = Quat::IDENTITY;
+        let b = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        assert_eq!(a.slerp(&b, 0.), a);
+        assert_eq!(a.slerp(&b, 1.), b);
+        assert_eq!(
+            a.slerp(&b, 0.5),
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 * 0.5)
+        );
+        assert_eq!(
+            a.slerp(&b, 0.25),
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 * 0.25)
+        );
+    }
+
+    #[test]
+    fn slerp_takes_shortest_path() {
+        let a = Quat::IDENTITY;
+        let b = -Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        let mid = a.slerp(&b, 0.5);
+        assert_eq!(
+            mid.rotate(&Vec3::X),
+            Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 * 0.5).rotate(&Vec3::X)
+        );
+        assert!(approx_eq!(
+            f32,
+            a.angle_between(&mid),
+            FRAC_PI_2 * 0.5,
+            PRECISION
+        ));
+    }
+
+    #[test]
+    fn nlerps() {
+        let a = Quat::IDENTITY;
+        let b = -Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        let mid = a.nlerp(&b, 0.5);
+        assert!(approx_eq!(f32, mid.norm(), 1., PRECISION));
+        assert_eq!(mid, Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 * 0.5));
+    }
+
+    #[test]
+    fn measures_angle_between() {
+        let a = Quat::from_axis_angle(Vec3::Y, 0.2);
+        let b = Quat::from_axis_angle(Vec3::Y, 1.);
+        assert!(approx_eq!(f32, a.angle_between(&b), 0.8, PRECISION));
+        assert!(approx_eq!(f32, a.angle_between(&-b), 0.8, PRECISION));
+        let c = Quat::from_axis_angle(Vec3::X, PI);
+        assert!(approx_eq!(
+            f32,
+            Quat::IDENTITY.angle_between(&c),
+            PI,
+            PRECISION
+        ));
+    }
+
+    #[test]
+    fn algebraic_ops_work() {
+        let q = Quat::new(1., 2., 3., 4.);
+        assert_eq!(q + q, q * 2.);
+        assert_eq!(q - q, Quat::ZERO);
+        assert_eq!(-q, Quat::new(-1., -2., -3., -4.));
+        assert_eq!(q[3], 4.);
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{
+    aabb::Aabb,
+    capsule::Capsule,
+    geometry::{Linear, Ray},
+    obb::Obb,
+    plane::Plane,
+    pt3::Pt3,
+    sphere::Sphere,
+    structure::{EuclideanSpace, VecSpace},
+    triangle::Triangle,
+    vec3::Vec3,
+};
+
+/// First intersection of a ray with a shape.
+///
+/// `t` is the ray parameter, so `point == ray.at(t)`, and `distance` is the
+/// Euclidean distance from the ray origin. `normal` is a unit vector facing
+/// the ray. Rays starting inside a solid shape hit it at `t == 0` with
+/// `normal` opposing the ray direction.
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    pub t: f32,
+    pub distance: f32,
+    pub point: Pt3,
+    pub normal: Vec3,
+    /// Weights of the vertices `a`, `b` and `c` for triangle hits.
+    pub barycentric: Option<Vec3>,
+}
+
+impl RayHit {
+    fn new(ray: &Ray, t: f32, normal: Vec3) -> Self {
+        Self {
+            t,
+            distance: t * ray.dir.norm(),
+            point: ray.at(t),
+            normal,
+            barycentric: None,
+        }
+    }
+
+    fn inside(ray: &Ray) -> Self {
+        Self::new(ray, 0., -ray.dir.normalize())
+    }
+}
+
+pub trait RayCast {
+    /// Closest hit with `0 <= t <= max_t`.
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit>;
+
+    fn raycast(&self, ray: &Ray) -> Option<RayHit> {
+        self.raycast_within(ray, f32::INFINITY)
+    }
+}
+
+fn facing(n: Vec3, dir: &Vec3) -> Vec3 {
+    if n.dot(dir) > 0. {
+        -n
+    } else {
+        n
+    }
+}
+
+impl RayCast for Triangle {
+    // Watertight ray/triangle test of Woop, Benthin and Wald, JCGT 2013.
+    // Shared edges are never missed, and both sides of the triangle are hit.
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        let d = ray.dir;
+        let abs = [d.x.abs(), d.y.abs(), d.z.abs()];
+        let kz = (0..3).max_by(|&i, &j| abs[i].total_cmp(&abs[j])).unwrap();
+        let mut kx = (kz + 1) % 3;
+        let mut ky = (kx + 1) % 3;
+        if d[kz] < 0. {
+            std::mem::swap(&mut kx, &mut ky);
+        }
+        if d[kz] == 0. {
+            return None;
+        }
+
+        // Shear and scale the vertices so the ray runs along +z
+        let sx = d[kx] / d[kz];
+        let sy = d[ky] / d[kz];
+        let sz = 1. / d[kz];
+        let a = Vec3::from(self.a - ray.origin);
+        let b = Vec3::from(self.b - ray.origin);
+        let c = Vec3::from(self.c - ray.origin);
+        let (ax, ay) = (a[kx] - sx * a[kz], a[ky] - sy * a[kz]);
+        let (bx, by) = (b[kx] - sx * b[kz], b[ky] - sy * b[kz]);
+        let (cx, cy) = (c[kx] - sx * c[kz], c[ky] - sy * c[kz]);
+
+        let mut u = cx * by - cy * bx;
+        let mut v = ax * cy - ay * cx;
+        let mut w = bx * ay - by * ax;
+
+        // Edges through the ray need exact signs, redo them in f64
+        if u == 0. || v == 0. || w == 0. {
+            let f = f64::from;
+            u = (f(cx) * f(by) - f(cy) * f(bx)) as f32;
+            v = (f(ax) * f(cy) - f(ay) * f(cx)) as f32;
+            w = (f(bx) * f(ay) - f(by) * f(ax)) as f32;
+        }
+
+        if (u < 0. || v < 0. || w < 0.) && (u > 0. || v > 0. || w > 0.) {
+            return None;
+        }
+        let det = u + v + w;
+        if det == 0. {
+            return None;
+        }
+
+        let t = (u * sz * a[kz] + v * sz * b[kz] + w * sz * c[kz]) / det;
+        if !(0. ..=max_t).contains(&t) {
+            return None;
+        }
+
+        let mut hit = RayHit::new(ray, t, facing(self.normal(), &d));
+        hit.barycentric = Some(Vec3::new(u, v, w) / det);
+        Some(hit)
+    }
+}
+
+impl RayCast for Sphere {
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        let m = Vec3::from(ray.origin - self.center);
+        let a = ray.dir.norm2();
+        let b = m.dot(&ray.dir);
+        let c = m.norm2() - self.radius * self.radius;
+        if c <= 0. {
+            return Some(RayHit::inside(ray));
+        }
+        if b > 0. {
+            return None;
+        }
+        let disc = b * b - a * c;
+        if disc < 0. {
+            return None;
+        }
+        let t = (-b - disc.sqrt()) / a;
+        if t > max_t {
+            return None;
+        }
+        let point = ray.at(t);
+        Some(RayHit::new(
+            ray,
+            t,
+            Vec3::from(point - self.center).normalize(),
+        ))
+    }
+}
+
+// Slab test, returns the entry parameter and the entry axis and sign
+pub(crate) fn slabs(
+    origin: &Vec3,
+    dir: &Vec3,
+    min: &Vec3,
+    max: &Vec3,
+    max_t: f32,
+) -> Option<(f32, Vec3)> {
+    let mut t_min = 0.;
+    let mut t_max = max_t;
+    let mut normal = None;
+
+    for k in 0..3 {
+        if dir[k] == 0. {
+            if origin[k] < min[k] || origin[k] > max[k] {
+                return None;
+            }
+            continue;
+        }
+        let inv = 1. / dir[k];
+        let (mut t1, mut t2) = ((min[k] - origin[k]) * inv, (max[k] - origin[k]) * inv);
+        let mut n = Vec3::ZERO;
+        n[k] = -1.;
+        if t1 > t2 {
+            std::mem::swap(&mut t1, &mut t2);
+            n[k] = 1.;
+        }
+        if t1 > t_min {
+            t_min = t1;
+            normal = Some(n);
+        }
+        t_max = t_max.min(t2);
+        if t_min > t_max {
+            return None;
+        }
+    }
+
+    Some((t_min, normal.unwrap_or(-dir.normalize())))
+}
+
+impl RayCast for Aabb {
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        let (t, normal) = slabs(
+            &ray.origin.into(),
+            &ray.dir,
+            &self.min.into(),
+            &self.max.into(),
+            max_t,
+        )?;
+        Some(RayHit::new(ray, t, normal))
+    }
+}
+
+impl RayCast for Obb {
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        // Cast in the box frame, then rotate the normal back
+        let origin = self.to_local(&ray.origin);
+        let [x, y, z] = self.axes;
+        let dir = Vec3::new(ray.dir.dot(&x), ray.dir.dot(&y), ray.dir.dot(&z));
+        let h = self.half_extents;
+        let (t, n) = slabs(&origin, &dir, &-h, &h, max_t)?;
+        Some(RayHit::new(ray, t, x * n.x + y * n.y + z * n.z))
+    }
+}
+
+impl RayCast for Capsule {
+    // The capsule is the union of the finite cylinder and the two end
+    // spheres, so its entry point is the earliest entry into any of them
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        if self.contains_point(&ray.origin) {
+            return Some(RayHit::inside(ray));
+        }
+
+        let r = self.radius;
+        let d = Vec3::from(self.b - self.a);
+        let m = Vec3::from(ray.origin - self.a);
+        let n = ray.dir;
+        let dd = d.norm2();
+        let md = m.dot(&d);
+        let nd = n.dot(&d);
+
+        // Side of the cylinder, see Ericson, RTCD section 5.3.7
+        let a = dd * n.norm2() - nd * nd;
+        let b = dd * m.dot(&n) - nd * md;
+        let c = dd * (m.norm2() - r * r) - md * md;
+        let disc = b * b - a * c;
+        let side = (a > f32::EPSILON * dd * n.norm2() && disc >= 0.)
+            .then(|| (-b - disc.sqrt()) / a)
+            .filter(|t| (0. ..=max_t).contains(t))
+            .filter(|t| (0. ..=dd).contains(&(md + t * nd)))
+            .map(|t| {
+                let p = ray.at(t);
+                let axis = self.a + d * ((md + t * nd) / dd);
+                RayHit::new(ray, t, Vec3::from(p - axis).normalize())
+            });
+
+        let caps = [self.a, self.b].map(|center| Sphere::new(center, r).raycast_within(ray, max_t));
+
+        [side, caps[0], caps[1]]
+            .into_iter()
+            .flatten()
+            .min_by(|h, k| h.t.total_cmp(&k.t))
+    }
+}
+
+impl RayCast for Plane {
+    // Planes are hit from either side
+    fn raycast_within(&self, ray: &Ray, max_t: f32) -> Option<RayHit> {
+        let t = ray.intersect_plane(self)?;
+        (t <= max_t).then(|| RayHit::new(ray, t, facing(self.normal().normalize(), &ray.dir)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use float_cmp::approx_eq;
+
+    use crate::{assert_approx_eq, mat3::Mat3, precision::PRECISION};
+
+    use super::*;
+
+    fn down(x: f32, y: f32) -> Ray {
+        Ray::new(Pt3::new(x, y, 5.), Vec3::new(0., 0., -2.))
+    }
+
+    #[test]
+    fn hits_triangle() {
+        let tri = Triangle::new(
+            Pt3::new(0., 0., 1.),
+            Pt3::new(2., 0., 1.),
+            Pt3::new(0., 2., 1.),
+        );
+        let hit = tri.raycast(&down(0.5, 0.5)).unwrap();
+        assert!(approx_eq!(f32, hit.t, 2., PRECISION));
+        assert!(approx_eq!(f32, hit.distance, 4., PRECISION));
+        assert_approx_eq!(hit.point, Pt3::new(0.5, 0.5, 1.));
+        assert_eq!(hit.normal, Vec3::Z);
+        assert_eq!(hit.barycentric.unwrap(), Vec3::new(0.5, 0.25, 0.25));
+
+        // Back faces are hit with the normal flipped towards the ray
+        let up = Ray::new(Pt3::new(0.5, 0.5, -1.), Vec3::Z);
+        assert_eq!(tri.raycast(&up).unwrap().normal, -Vec3::Z);
+
+        assert!(tri.raycast(&down(1.5, 1.5)).is_none());
+        assert!(tri.raycast_within(&down(0.5, 0.5), 1.).is_none());
+    }
+
+    #[test]
+    fn triangle_edges_are_watertight() {
+        // Two triangles sharing the diagonal of a unit square; rays through
+        // the diagonal must hit at least one of them
+        let a = Pt3::new(0., 0., 0.);
+        let b = Pt3::new(1., 0., 0.);
+        let c = Pt3::new(1., 1., 0.);
+        let d = Pt3::new(0., 1., 0.);
+        let t1 = Triangle::new(a, b, c);
+        let t2 = Triangle::new(a, c, d);
+        let dir = Vec3::new(0.01, -0.003, -1.);
+        for i in 1..100 {
+            let s = i as f32 / 100.;
+            let ray = Ray::new(Pt3::new(s, s, 0.) - dir * 2., dir);
+            assert!(t1.raycast(&ray).is_some() || t2.raycast(&ray).is_some());
+        }
+    }
+
+    #[test]
+    fn hits_sphere() {
+        let s = Sphere::new(Pt3::new(0., 0., 0.), 1.);
+        let hit = s.raycast(&down(0., 0.)).unwrap();
+        assert!(approx_eq!(f32, hit.t, 2., PRECISION));
+        assert_eq!(hit.normal, Vec3::Z);
+        assert!(s.raycast(&down(1.5, 0.)).is_none());
+        let inside = Ray::new(Pt3::new(0., 0., 0.5), Vec3::X);
+        assert_eq!(s.raycast(&inside).unwrap().t, 0.);
+    }
+
+    #[test]
+    fn hits_aabb() {
+        let b = Aabb::new(Pt3::new(-1., -1., -1.), Pt3::new(1., 1., 1.));
+        let hit = b.raycast(&down(0.5, -0.5)).unwrap();
+        assert!(approx_eq!(f32, hit.t, 2., PRECISION));
+        assert_eq!(hit.normal, Vec3::Z);
+
+        let side = Ray::new(Pt3::new(-3., 0.5, 0.), Vec3::X);
+        let hit = b.raycast(&side).unwrap();
+        assert_approx_eq!(hit.point, Pt3::new(-1., 0.5, 0.));
+        assert_eq!(hit.normal, -Vec3::X);
+
+        // Parallel to a slab and outside of it
+        assert!(b
+            .raycast(&Ray::new(Pt3::new(-3., 2., 0.), Vec3::X))
+            .is_none());
+        assert!(b.raycast_within(&side, 1.).is_none());
+    }
+
+    #[test]
+    fn hits_obb() {
+        let r = Mat3::from_angle_z(FRAC_PI_4);
+        let b = Obb::new(
+            Pt3::new(0., 0., 0.),
+            [r * Vec3::X, r * Vec3::Y, Vec3::Z],
+            Vec3::new(1., 1., 1.),
+        );
+        let ray = Ray::new(Pt3::new(-5., 0., 0.), Vec3::X);
+        let hit = b.raycast(&ray).unwrap();
+        assert_approx_eq!(hit.point, Pt3::new(-2_f32.sqrt(), 0., 0.));
+        assert!(approx_eq!(
+            f32,
+            hit.normal.dot(&-Vec3::X),
+            0.5_f32.sqrt(),
+            PRECISION
+        ));
+    }
+
+    #[test]
+    fn hits_capsule() {
+        let c = Capsule::new(Pt3::new(0., 0., -1.), Pt3::new(0., 0., 1.), 0.5);
+
+        // Side
+        let ray = Ray::new(Pt3::new(-3., 0., 0.), Vec3::X);
+        let hit = c.raycast(&ray).unwrap();
+        assert_approx_eq!(hit.point, Pt3::new(-0.5, 0., 0.));
+        assert_eq!(hit.normal, -Vec3::X);
+
+        // End cap, along the axis
+        let hit = c.raycast(&down(0., 0.)).unwrap();
+        assert_approx_eq!(hit.point, Pt3::new(0., 0., 1.5));
+        assert_eq!(hit.normal, Vec3::Z);
+
+        assert!(c.raycast(&down(0.6, 0.)).is_none());
+        assert!(c.raycast_within(&ray, 1.).is_none());
+    }
+
+    #[test]
+    fn hits_plane() {
+        let f = Plane::new(0., 0., 2., -2.);
+        let hit = f.raycast(&down(3., 4.)).unwrap();
+        assert_approx_eq!(hit.point, Pt3::new(3., 4., 1.));
+        assert_eq!(hit.normal, Vec3::Z);
+        let up = Ray::new(Pt3::new(0., 0., -1.), Vec3::Z);
+        assert_eq!(f.raycast(&up).unwrap().normal, -Vec3::Z);
+        assert!(f
+            .raycast(&Ray::new(Pt3::new(0., 0., 0.), Vec3::X))
+            .is_none());
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{
+    obb::Obb,
+    precision::SINGULAR_EPSILON,
+    pt3::Pt3,
+    structure::{EuclideanSpace, Mat, VecSpace},
+    triangle::Triangle,
+    vec3::Vec3,
+};
+
+/// Axis of least overlap between two shapes. Moving the second shape by
+/// `normal * depth` leaves them touching.
+#[derive(Clone, Copy, Debug)]
+pub struct Overlap {
+    pub normal: Vec3,
+    pub depth: f32,
+}
+
+/// Separating axis test between convex polyhedra.
+pub trait SeparatingAxis<Rhs = Self> {
+    /// Overlap along the candidate axis where it is smallest, `None` when
+    /// one of the axes separates the shapes.
+    fn sat(&self, other: &Rhs) -> Option<Overlap>;
+}
+
+impl Overlap {
+    fn none() -> Self {
+        Self {
+            normal: Vec3::ZERO,
+            depth: f32::INFINITY,
+        }
+    }
+
+    fn flip(self) -> Self {
+        Self {
+            normal: -self.normal,
+            depth: self.depth,
+        }
+    }
+
+    /// Projections of the shapes on the unit `axis` are intervals of radii
+    /// `ra` and `rb` whose centers are `dist` apart. Returns whether they
+    /// overlap, keeping the smallest overlap.
+    fn update(&mut self, axis: Vec3, ra: f32, rb: f32, dist: f32) -> bool {
+        let depth = ra + rb - dist.abs();
+        if depth < 0. {
+            return false;
+        }
+        if depth < self.depth {
+            self.normal = if dist < 0. { -axis } else { axis };
+            self.depth = depth;
+        }
+        true
+    }
+}
+
+// Warning: cross products of nearly parallel edges are tiny and point in
+// arbitrary directions. They are skipped; the face normals and in-plane
+// edge normals cover the separating directions they would have given
+fn cross_axis(e: &Vec3, f: &Vec3) -> Option<Vec3> {
+    let axis = e.cross(f);
+    let len2 = axis.norm2();
+    if len2 <= SINGULAR_EPSILON * e.norm2() * f.norm2() {
+        None
+    } else {
+        Some(axis / len2.sqrt())
+    }
+}
+
+/// Center and radius of the projection of points on `axis`.
+fn project_points(points: &[Pt3], axis: &Vec3) -> (f32, f32) {
+    let (lo, hi) = points
+        .iter()
+        .map(|p| Vec3::from(*p).dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| {
+            (lo.min(d), hi.max(d))
+        });
+    ((lo + hi) * 0.5, (hi - lo) * 0.5)
+}
+
+fn project_obb(b: &Obb, axis: &Vec3) -> (f32, f32) {
+    let h = b.half_extents;
+    let radius = (0..3).fold(0., |r, k| r + h[k] * b.axes[k].dot(axis).abs());
+    (Vec3::from(b.center).dot(axis), radius)
+}
+
+fn edges(t: &Triangle) -> [Vec3; 3] {
+    [
+        Vec3::from(t.b - t.a),
+        Vec3::from(t.c - t.b),
+        Vec3::from(t.a - t.c),
+    ]
+}
+
+/// Tests every axis in turn, `project_a` and `project_b` give the center
+/// and radius of each shape along an axis.
+fn search(
+    axes: impl IntoIterator<Item = Vec3>,
+    project_a: impl Fn(&Vec3) -> (f32, f32),
+    project_b: impl Fn(&Vec3) -> (f32, f32),
+) -> Option<Overlap> {
+    let mut best = Overlap::none();
+    for axis in axes {
+        let (ca, ra) = project_a(&axis);
+        let (cb, rb) = project_b(&axis);
+        if !best.update(axis, ra, rb, cb - ca) {
+            return None;
+        }
+    }
+    Some(best)
+}
+
+impl SeparatingAxis for Obb {
+    /// See Gottschalk et al., "OBBTree: A Hierarchical Structure for Rapid
+    /// Interference Detection".
+    fn sat(&self, other: &Obb) -> Option<Overlap> {
+        let (ha, hb) = (self.half_extents, other.half_extents);
+        // Rotation of the other box and its center in this box's frame
+        let r = self.rotation().transpose() * other.rotation();
+        let t = self.to_local(&other.center);
+
+        let mut best = Overlap::none();
+        for i in 0..3 {
+            let rb = (0..3).fold(0., |s, j| s + hb[j] * r[i][j].abs());
+            if !best.update(self.axes[i], ha[i], rb, t[i]) {
+                return None;
+            }
+        }
+        for j in 0..3 {
+            let ra = (0..3).fold(0., |s, i| s + ha[i] * r[i][j].abs());
+            let dist = (0..3).fold(0., |s, i| s + t[i] * r[i][j]);
+            if !best.update(other.axes[j], ra, hb[j], dist) {
+                return None;
+            }
+        }
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                // |a_i x b_j| is the sine of the angle between the axes
+                let len2 = 1. - r[i][j] * r[i][j];
+                if len2 <= SINGULAR_EPSILON {
+                    continue;
+                }
+                let len = len2.sqrt();
+                let ra = ha[i1] * r[i2][j].abs() + ha[i2] * r[i1][j].abs();
+                let rb = hb[j1] * r[i][j2].abs() + hb[j2] * r[i][j1].abs();
+                let dist = t[i2] * r[i1][j] - t[i1] * r[i2][j];
+                let axis = self.axes[i].cross(&other.axes[j]) / len;
+                if !best.update(axis, ra / len, rb / len, dist / len) {
+                    return None;
+                }
+            }
+        }
+        Some(best)
+    }
+}
+
+impl SeparatingAxis<Triangle> for Obb {
+    fn sat(&self, other: &Triangle) -> Option<Overlap> {
+        let n = other.scaled_normal();
+        let es = edges(other);
+        let axes = self
+            .axes
+            .into_iter()
+            .chain(cross_axis(&es[0], &es[1]))
+            .chain(
+                self.axes
+                    .iter()
+                    .flat_map(|a| es.iter().filter_map(|e| cross_axis(a, e))),
+            )
+            .chain(es.iter().filter_map(|e| cross_axis(&n, e)));
+        let vertices = other.vertices();
+        search(
+            axes,
+            |axis| project_obb(self, axis),
+            |axis| project_points(&vertices, axis),
+        )
+    }
+}
+
+impl SeparatingAxis<Obb> for Triangle {
+    fn sat(&self, other: &Obb) -> Option<Overlap> {
+        other.sat(self).map(Overlap::flip)
+    }
+}
+
+impl SeparatingAxis for Triangle {
+    fn sat(&self, other: &Triangle) -> Option<Overlap> {
+        let (na, nb) = (self.scaled_normal(), other.scaled_normal());
+        let (ea, eb) = (edges(self), edges(other));
+        // The in-plane edge normals separate coplanar triangles, whose edge
+        // cross products all point along the shared normal
+        let axes = [(ea[0], ea[1]), (eb[0], eb[1])]
+            .into_iter()
+            .filter_map(|(e, f)| cross_axis(&e, &f))
+            .chain(
+                ea.iter()
+                    .flat_map(|e| eb.iter().filter_map(|f| cross_axis(e, f))),
+            )
+            .chain(ea.iter().filter_map(|e| cross_axis(&na, e)))
+            .chain(eb.iter().filter_map(|f| cross_axis(&nb, f)));
+        let (va, vb) = (self.vertices(), other.vertices());
+        search(
+            axes,
+            |axis| project_points(&va, axis),
+            |axis| project_points(&vb, axis),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use float_cmp::approx_eq;
+
+    use crate::{aabb::Aabb, mat3::Mat3, structure::SquareMat, support::Support};
+
+    use super::*;
+
+    fn cube(center: Pt3, rotation: Mat3) -> Obb {
+        let axes = [Vec3::X, Vec3::Y, Vec3::Z].map(|a| rotation * a);
+        Obb::new(center, axes, Vec3::new(1., 1., 1.))
+    }
+
+    #[test]
+    fn separates_boxes() {
+        let a = cube(Pt3::new(0., 0., 0.), Mat3::IDENTITY);
+        let b = cube(Pt3::new(2.5, 0., 0.), Mat3::IDENTITY);
+        assert!(a.sat(&b).is_none());
+
+        let b = cube(Pt3::new(-1.5, 0.5, 0.), Mat3::IDENTITY);
+        let o = a.sat(&b).unwrap();
+        assert_eq!(o.normal, -Vec3::X);
+        assert!(approx_eq!(f32, o.depth, 0.5, epsilon = 1e-5));
+
+        // Turned 45 degrees, the corner reaches sqrt(2) along x
+        let b = cube(Pt3::new(2.3, 0., 0.), Mat3::from_angle_z(FRAC_PI_4));
+        let o = a.sat(&b).unwrap();
+        assert_eq!(o.normal, Vec3::X);
+        assert!(approx_eq!(f32, o.depth, 2f32.sqrt() - 1.3, epsilon = 1e-5));
+        let b = cube(Pt3::new(2.5, 0., 0.), Mat3::from_angle_z(FRAC_PI_4));
+        assert!(a.sat(&b).is_none());
+    }
+
+    #[test]
+    fn separates_turned_boxes() {
+        let a = cube(Pt3::new(0., 0., 0.), Mat3::from_angle_x(FRAC_PI_4));
+        let r = Mat3::from_angle_z(FRAC_PI_4);
+        let b = cube(Pt3::new(2.3, 2.3, 0.), r);
+        assert!(a.sat(&b).is_none());
+        assert!(!a.intersects(&b));
+
+        // Matches the penetration found by EPA on the same boxes
+        for k in 0..8 {
+            let s = k as f32 * 0.1;
+            let b = cube(
+                Pt3::new(1.2 + s, 1.4 - s, 0.3 * s),
+                Mat3::from_angle_y(0.3 + s) * Mat3::from_angle_z(0.7 - s),
+            );
+            let o = a.sat(&b).unwrap();
+            let c = a.penetration(&b).unwrap();
+            assert!(approx_eq!(f32, o.depth, c.depth, epsilon = 1e-3));
+            assert!(o.normal.dot(&c.normal) > 0.99);
+        }
+    }
+
+    #[test]
+    fn handles_nearly_parallel_boxes() {
+        let a = cube(Pt3::new(0., 0., 0.), Mat3::IDENTITY);
+        for angle in [0., 1e-7, 1e-5, 1e-3] {
+            let b = cube(Pt3::new(1.8, 0.3, -0.2), Mat3::from_angle_z(angle));
+            let o = a.sat(&b).unwrap();
+            assert!(o.normal.x > 0.99);
+            assert!(approx_eq!(f32, o.depth, 0.2, epsilon = 2e-3));
+            assert!(o.depth.is_finite());
+
+            let b = cube(Pt3::new(2.1, 0.3, -0.2), Mat3::from_angle_z(angle));
+            assert!(a.sat(&b).is_none());
+        }
+    }
+
+    #[test]
+    fn tests_boxes_against_triangles() {
+        let b = Obb::from_aabb(&Aabb::new(Pt3::new(-1., -1., -1.), Pt3::new(1., 1., 1.)));
+        let t = Triangle::new(
+            Pt3::new(-3., -3., 0.8),
+            Pt3::new(3., -3., 0.8),
+            Pt3::new(0., 3., 0.8),
+        );
+        let o = b.sat(&t).unwrap();
+        assert_eq!(o.normal, Vec3::Z);
+        assert!(approx_eq!(f32, o.depth, 0.2, epsilon = 1e-5));
+        let o = t.sat(&b).unwrap();
+        assert_eq!(o.normal, -Vec3::Z);
+
+        let above = Triangle::new(
+            Pt3::new(-3., -3., 1.2),
+            Pt3::new(3., -3., 1.2),
+            Pt3::new(0., 3., 1.2),
+        );
+        assert!(b.sat(&above).is_none());
+
+        // Slanted wall passing by a vertical edge of the box
+        let corner = Triangle::new(
+            Pt3::new(2.1, 0., -5.),
+            Pt3::new(0., 2.1, -5.),
+            Pt3::new(0., 2.1, 5.),
+        );
+        assert!(b.sat(&corner).is_none());
+        let corner = Triangle::new(corner.a, corner.b + Vec3::Z, corner.c - Vec3::X * 0.2);
+        assert_eq!(b.sat(&corner).is_some(), b.intersects(&corner));
+    }
+
+    #[test]
+    fn tests_triangles() {
+        let a = Triangle::new(
+            Pt3::new(-1., -1., 0.),
+            Pt3::new(1., -1., 0.),
+            Pt3::new(0., 1., 0.),
+        );
+        let piercing = Triangle::new(
+            Pt3::new(0., -0.5, -1.),
+            Pt3::new(0., 0., 0.1),
+            Pt3::new(0., 0.5, -1.),
+        );
+        let o = a.sat(&piercing).unwrap();
+        assert!(approx_eq!(f32, o.depth, 0.1, epsilon = 1e-5));
+        assert_eq!(o.normal, -Vec3::Z);
+
+        let lifted = Triangle::new(
+            piercing.a + Vec3::Z * 1.2,
+            piercing.b + Vec3::Z * 1.2,
+            piercing.c + Vec3::Z * 1.2,
+        );
+        assert!(lifted.sat(&a).is_none());
+
+        // Coplanar triangles are only separated by in-plane axes
+        let beside = Triangle::new(
+            Pt3::new(1.1, 1., 0.),
+            Pt3::new(0.6, 0., 0.),
+            Pt3::new(2., 0., 0.),
+        );
+        assert!(a.sat(&beside).is_none());
+        let overlapping = Triangle::new(
+            Pt3::new(0.9, 1., 0.),
+            Pt3::new(0.4, 0., 0.),
+            Pt3::new(2., 0., 0.),
+        );
+        let o = a.sat(&overlapping).unwrap();
+        assert!(approx_eq!(f32, o.depth, 0., epsilon = 1e-6));
+        assert_eq!(o.normal.z.abs(), 1.);
+    }
+}
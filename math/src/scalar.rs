@@ -0,0 +1,91 @@
+use crate::precision::{PRECISION, PRECISION_F64, SINGULAR_EPSILON};
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Floating point type the math traits are generic over, implemented for
+/// `f32` and `f64`.
+pub trait Scalar
+where
+    Self: Copy + Debug + PartialOrd,
+    Self: Add<Output = Self> + AddAssign,
+    Self: Sub<Output = Self> + SubAssign,
+    Self: Mul<Output = Self> + MulAssign,
+    Self: Div<Output = Self> + DivAssign,
+    Self: Neg<Output = Self>,
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const INFINITY: Self;
+    /// Machine epsilon, the gap between 1 and the next representable value.
+    const EPSILON: Self;
+    /// Ratio threshold used by `SquareMat::invert`, see `SINGULAR_EPSILON`.
+    const SINGULAR_EPSILON: Self;
+    /// Absolute tolerance `assert_approx_eq!` uses by default, the same as
+    /// the `PartialEq` impls.
+    const DEFAULT_EPSILON: Self;
+    /// Relative tolerance `assert_rel_eq!` uses by default.
+    const DEFAULT_MAX_RELATIVE: Self;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn is_finite(self) -> bool;
+    /// Bit pattern, widened to 64 bits for `f32`.
+    fn bits(self) -> u64;
+    /// Number of representable values between `self` and `other`, saturated
+    /// when their signs differ or either is NaN.
+    fn ulps(self, other: Self) -> u64;
+}
+
+macro_rules! impl_scalar {
+    ($S:ident, $singular_epsilon:expr, $epsilon:expr, $max_relative:expr) => {
+        impl Scalar for $S {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+            const INFINITY: Self = $S::INFINITY;
+            const EPSILON: Self = $S::EPSILON;
+            const SINGULAR_EPSILON: Self = $singular_epsilon;
+            const DEFAULT_EPSILON: Self = $epsilon;
+            const DEFAULT_MAX_RELATIVE: Self = $max_relative;
+
+            fn sqrt(self) -> Self {
+                $S::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                $S::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                $S::max(self, other)
+            }
+
+            fn is_finite(self) -> bool {
+                $S::is_finite(self)
+            }
+
+            fn bits(self) -> u64 {
+                $S::to_bits(self) as u64
+            }
+
+            fn ulps(self, other: Self) -> u64 {
+                if self == other {
+                    0
+                } else if self.is_nan()
+                    || other.is_nan()
+                    || self.is_sign_negative() != other.is_sign_negative()
+                {
+                    u64::MAX
+                } else {
+                    // Floats of one sign are ordered like their bit patterns
+                    self.bits().abs_diff(other.bits())
+                }
+            }
+        }
+    };
+}
+
+impl_scalar!(f32, SINGULAR_EPSILON, PRECISION.epsilon, 1e-5);
+impl_scalar!(f64, 1e-12, PRECISION_F64.epsilon, 1e-12);
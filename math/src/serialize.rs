@@ -0,0 +1,126 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    affine3::Affine3, dmat3::DMat3, dmat4::DMat4, dplane::DPlane, dpt3::DPt3, dquat::DQuat,
+    dual_quat::DualQuat, dvec3::DVec3, dvec4::DVec4, mat2::Mat2, mat3::Mat3, mat4::Mat4,
+    plane::Plane, pt2::Pt2, pt3::Pt3, quat::Quat, transform::Transform, vec2::Vec2, vec3::Vec3,
+    vec4::Vec4,
+};
+
+// Goes through the array form of `impl_conversions!`, so vectors encode as
+// tuples and matrices as tuples of rows. `Transform` encodes as its
+// translation, rotation and scale, and `DualQuat` as its real and dual parts
+macro_rules! impl_serde {
+    ($A:ty => $B:ty) => {
+        impl Serialize for $A {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$B>::from(self).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $A {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <$B>::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
+
+impl_serde!(Vec2 => [f32; 2]);
+impl_serde!(Vec3 => [f32; 3]);
+impl_serde!(Vec4 => [f32; 4]);
+impl_serde!(Pt2 => [f32; 2]);
+impl_serde!(Pt3 => [f32; 3]);
+impl_serde!(Quat => [f32; 4]);
+impl_serde!(Plane => [f32; 4]);
+impl_serde!(Mat2 => [[f32; 2]; 2]);
+impl_serde!(Mat3 => [[f32; 3]; 3]);
+impl_serde!(Mat4 => [[f32; 4]; 4]);
+impl_serde!(Affine3 => [[f32; 4]; 3]);
+impl_serde!(Transform => (Vec3, Quat, Vec3));
+impl_serde!(DualQuat => [[f32; 4]; 2]);
+
+impl_serde!(DVec3 => [f64; 3]);
+impl_serde!(DVec4 => [f64; 4]);
+impl_serde!(DPt3 => [f64; 3]);
+impl_serde!(DQuat => [f64; 4]);
+impl_serde!(DPlane => [f64; 4]);
+impl_serde!(DMat3 => [[f64; 3]; 3]);
+impl_serde!(DMat4 => [[f64; 4]; 4]);
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use serde::de::DeserializeOwned;
+
+    use crate::{assert_approx_eq, structure::SquareMat};
+
+    use super::*;
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T, json: &str) {
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+    }
+
+    #[test]
+    fn encodes_vectors_as_arrays() {
+        round_trip(Vec3::new(1., -2., 0.5), "[1.0,-2.0,0.5]");
+        round_trip(Vec4::new(1., 2., 3., 4.), "[1.0,2.0,3.0,4.0]");
+        round_trip(Quat::IDENTITY, "[0.0,0.0,0.0,1.0]");
+        round_trip(DVec3::new(0.25, 0., -1.), "[0.25,0.0,-1.0]");
+
+        let p: Pt3 = serde_json::from_str("[4.0,5.0,6.0]").unwrap();
+        assert_approx_eq!(p, Pt3::new(4., 5., 6.));
+        assert_eq!(serde_json::to_string(&p).unwrap(), "[4.0,5.0,6.0]");
+        let f: Plane = serde_json::from_str("[0.0,1.0,0.0,-2.0]").unwrap();
+        assert_eq!(f.normal(), Vec3::Y);
+        assert_eq!(f.d, -2.);
+    }
+
+    #[test]
+    fn encodes_matrices_as_rows() {
+        let m = Mat3::new(
+            1., 2., 3., //
+            4., 5., 6., //
+            7., 8., 9.,
+        );
+        round_trip(m, "[[1.0,2.0,3.0],[4.0,5.0,6.0],[7.0,8.0,9.0]]");
+        let json = serde_json::to_string(&Mat4::IDENTITY).unwrap();
+        assert_eq!(serde_json::from_str::<Mat4>(&json).unwrap(), Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn encodes_transforms() {
+        let a = Affine3::new(
+            &Mat3::from_diagonal(Vec3::new(2., 3., 4.)),
+            Vec3::new(1., 0., -1.),
+        );
+        round_trip(
+            a,
+            "[[2.0,0.0,0.0,1.0],[0.0,3.0,0.0,0.0],[0.0,0.0,4.0,-1.0]]",
+        );
+        round_trip(
+            DualQuat::from_translation(Vec3::new(2., 0., 0.)),
+            "[[0.0,0.0,0.0,1.0],[1.0,0.0,0.0,0.0]]",
+        );
+
+        let json = "[[1.0,2.0,3.0],[0.0,0.0,0.0,1.0],[2.0,1.0,1.0]]";
+        let t: Transform = serde_json::from_str(json).unwrap();
+        assert_eq!(t.translation, Vec3::new(1., 2., 3.));
+        assert_eq!(t.rotation, Quat::IDENTITY);
+        assert_eq!(t.scale, Vec3::new(2., 1., 1.));
+        assert_eq!(serde_json::to_string(&t).unwrap(), json);
+
+        let f: DPlane = serde_json::from_str("[0.0,0.0,1.0,-1e-9]").unwrap();
+        assert_eq!(f.d, -1e-9);
+        assert_eq!(serde_json::to_string(&f).unwrap(), "[0.0,0.0,1.0,-1e-9]");
+    }
+
+    #[test]
+    fn rejects_wrong_lengths() {
+        assert!(serde_json::from_str::<Vec3>("[1.0,2.0]").is_err());
+        assert!(serde_json::from_str::<Mat3>("[[1.0,2.0,3.0]]").is_err());
+        assert!(serde_json::from_str::<Transform>("[[1.0,2.0,3.0],[0.0,0.0,0.0,1.0]]").is_err());
+    }
+}
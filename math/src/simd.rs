@@ -0,0 +1,449 @@
+use crate::{mat4::Mat4, precision::is_invertible, quat::Quat, vec4::Vec4};
+
+// 128-bit lane kernels for `Vec4`, `Mat4` and `Quat`. With the `simd` feature
+// `F32x4` wraps SSE2 or NEON registers, otherwise (or on other targets) it is
+// a plain array, so the same kernels double as the scalar fallback.
+
+pub(crate) const fn shuffle_mask(x: i32, y: i32, z: i32, w: i32) -> i32 {
+    x | (y << 2) | (z << 4) | (w << 6)
+}
+
+#[cfg(all(
+    feature = "simd",
+    any(
+        target_arch = "x86_64",
+        all(target_arch = "x86", target_feature = "sse2")
+    )
+))]
+mod lanes {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use crate::{quat::Quat, vec4::Vec4};
+
+    // SSE2 is part of the x86_64 baseline and required above for x86, so
+    // the intrinsics below are always available
+    #[derive(Clone, Copy)]
+    pub(crate) struct F32x4(__m128);
+
+    impl F32x4 {
+        pub(crate) fn from_vec4(v: &Vec4) -> Self {
+            // `Vec4` is `repr(C, align(16))` with the `simd` feature
+            Self(unsafe { _mm_load_ps(v as *const Vec4 as *const f32) })
+        }
+
+        pub(crate) fn from_quat(q: &Quat) -> Self {
+            Self(unsafe { _mm_load_ps(q as *const Quat as *const f32) })
+        }
+
+        pub(crate) fn to_vec4(self) -> Vec4 {
+            let mut v = Vec4::ZERO;
+            unsafe { _mm_store_ps(&mut v as *mut Vec4 as *mut f32, self.0) };
+            v
+        }
+
+        pub(crate) fn to_quat(self) -> Quat {
+            let mut q = Quat::ZERO;
+            unsafe { _mm_store_ps(&mut q as *mut Quat as *mut f32, self.0) };
+            q
+        }
+
+        pub(crate) fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+            unsafe { Self(_mm_set_ps(w, z, y, x)) }
+        }
+
+        pub(crate) fn splat(s: f32) -> Self {
+            unsafe { Self(_mm_set1_ps(s)) }
+        }
+
+        pub(crate) fn add(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_add_ps(self.0, rhs.0)) }
+        }
+
+        pub(crate) fn sub(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_sub_ps(self.0, rhs.0)) }
+        }
+
+        pub(crate) fn mul(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_mul_ps(self.0, rhs.0)) }
+        }
+
+        pub(crate) fn shuffle<const MASK: i32>(self) -> Self {
+            unsafe { Self(_mm_shuffle_ps::<MASK>(self.0, self.0)) }
+        }
+
+        pub(crate) fn sum(self) -> f32 {
+            unsafe {
+                let t = _mm_add_ps(self.0, _mm_movehl_ps(self.0, self.0));
+                let t = _mm_add_ss(t, _mm_shuffle_ps::<1>(t, t));
+                _mm_cvtss_f32(t)
+            }
+        }
+
+        pub(crate) fn transpose(r: [Self; 4]) -> [Self; 4] {
+            unsafe {
+                let t0 = _mm_unpacklo_ps(r[0].0, r[1].0);
+                let t1 = _mm_unpacklo_ps(r[2].0, r[3].0);
+                let t2 = _mm_unpackhi_ps(r[0].0, r[1].0);
+                let t3 = _mm_unpackhi_ps(r[2].0, r[3].0);
+                [
+                    Self(_mm_movelh_ps(t0, t1)),
+                    Self(_mm_movehl_ps(t1, t0)),
+                    Self(_mm_movelh_ps(t2, t3)),
+                    Self(_mm_movehl_ps(t3, t2)),
+                ]
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod lanes {
+    use std::arch::aarch64::*;
+
+    use crate::{quat::Quat, vec4::Vec4};
+
+    // NEON is part of the aarch64 baseline
+    #[derive(Clone, Copy)]
+    pub(crate) struct F32x4(float32x4_t);
+
+    impl F32x4 {
+        pub(crate) fn from_vec4(v: &Vec4) -> Self {
+            // `Vec4` is `repr(C, align(16))` with the `simd` feature
+            Self(unsafe { vld1q_f32(v as *const Vec4 as *const f32) })
+        }
+
+        pub(crate) fn from_quat(q: &Quat) -> Self {
+            Self(unsafe { vld1q_f32(q as *const Quat as *const f32) })
+        }
+
+        pub(crate) fn to_vec4(self) -> Vec4 {
+            let mut v = Vec4::ZERO;
+            unsafe { vst1q_f32(&mut v as *mut Vec4 as *mut f32, self.0) };
+            v
+        }
+
+        pub(crate) fn to_quat(self) -> Quat {
+            let mut q = Quat::ZERO;
+            unsafe { vst1q_f32(&mut q as *mut Quat as *mut f32, self.0) };
+            q
+        }
+
+        fn to_array(self) -> [f32; 4] {
+            let mut a = [0.; 4];
+            unsafe { vst1q_f32(a.as_mut_ptr(), self.0) };
+            a
+        }
+
+        pub(crate) fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+            let a = [x, y, z, w];
+            Self(unsafe { vld1q_f32(a.as_ptr()) })
+        }
+
+        pub(crate) fn splat(s: f32) -> Self {
+            unsafe { Self(vdupq_n_f32(s)) }
+        }
+
+        pub(crate) fn add(self, rhs: Self) -> Self {
+            unsafe { Self(vaddq_f32(self.0, rhs.0)) }
+        }
+
+        pub(crate) fn sub(self, rhs: Self) -> Self {
+            unsafe { Self(vsubq_f32(self.0, rhs.0)) }
+        }
+
+        pub(crate) fn mul(self, rhs: Self) -> Self {
+            unsafe { Self(vmulq_f32(self.0, rhs.0)) }
+        }
+
+        pub(crate) fn shuffle<const MASK: i32>(self) -> Self {
+            // NEON has no immediate four-lane shuffle, go through memory
+            let a = self.to_array();
+            let lane = |i: i32| a[((MASK >> (2 * i)) & 3) as usize];
+            Self::new(lane(0), lane(1), lane(2), lane(3))
+        }
+
+        pub(crate) fn sum(self) -> f32 {
+            unsafe { vaddvq_f32(self.0) }
+        }
+
+        pub(crate) fn transpose(r: [Self; 4]) -> [Self; 4] {
+            unsafe {
+                let t0 = vtrnq_f32(r[0].0, r[1].0);
+                let t1 = vtrnq_f32(r[2].0, r[3].0);
+                [
+                    Self(vcombine_f32(vget_low_f32(t0.0), vget_low_f32(t1.0))),
+                    Self(vcombine_f32(vget_low_f32(t0.1), vget_low_f32(t1.1))),
+                    Self(vcombine_f32(vget_high_f32(t0.0), vget_high_f32(t1.0))),
+                    Self(vcombine_f32(vget_high_f32(t0.1), vget_high_f32(t1.1))),
+                ]
+            }
+        }
+    }
+}
+
+#[cfg(not(all(
+    feature = "simd",
+    any(
+        target_arch = "x86_64",
+        all(target_arch = "x86", target_feature = "sse2"),
+        target_arch = "aarch64"
+    )
+)))]
+mod lanes {
+    use crate::{quat::Quat, vec4::Vec4};
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct F32x4([f32; 4]);
+
+    impl F32x4 {
+        pub(crate) fn from_vec4(v: &Vec4) -> Self {
+            Self([v.x, v.y, v.z, v.w])
+        }
+
+        pub(crate) fn from_quat(q: &Quat) -> Self {
+            Self([q.x, q.y, q.z, q.w])
+        }
+
+        pub(crate) fn to_vec4(self) -> Vec4 {
+            let [x, y, z, w] = self.0;
+            Vec4::new(x, y, z, w)
+        }
+
+        pub(crate) fn to_quat(self) -> Quat {
+            let [x, y, z, w] = self.0;
+            Quat::new(x, y, z, w)
+        }
+
+        pub(crate) fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+            Self([x, y, z, w])
+        }
+
+        pub(crate) fn splat(s: f32) -> Self {
+            Self([s; 4])
+        }
+
+        pub(crate) fn add(self, rhs: Self) -> Self {
+            Self(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+        }
+
+        pub(crate) fn sub(self, rhs: Self) -> Self {
+            Self(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+        }
+
+        pub(crate) fn mul(self, rhs: Self) -> Self {
+            Self(std::array::from_fn(|i| self.0[i] * rhs.0[i]))
+        }
+
+        pub(crate) fn shuffle<const MASK: i32>(self) -> Self {
+            Self(std::array::from_fn(|i| {
+                self.0[((MASK >> (2 * i)) & 3) as usize]
+            }))
+        }
+
+        pub(crate) fn sum(self) -> f32 {
+            (self.0[0] + self.0[2]) + (self.0[1] + self.0[3])
+        }
+
+        pub(crate) fn transpose(r: [Self; 4]) -> [Self; 4] {
+            std::array::from_fn(|j| Self(std::array::from_fn(|i| r[i].0[j])))
+        }
+    }
+}
+
+use lanes::F32x4;
+
+const YZXW: i32 = shuffle_mask(1, 2, 0, 3);
+const ZXYW: i32 = shuffle_mask(2, 0, 1, 3);
+
+fn rows(m: &Mat4) -> [F32x4; 4] {
+    [
+        F32x4::from_vec4(&m.a),
+        F32x4::from_vec4(&m.b),
+        F32x4::from_vec4(&m.c),
+        F32x4::from_vec4(&m.d),
+    ]
+}
+
+fn from_rows(r: [F32x4; 4]) -> Mat4 {
+    Mat4::from_columns(
+        r[0].to_vec4(),
+        r[1].to_vec4(),
+        r[2].to_vec4(),
+        r[3].to_vec4(),
+    )
+}
+
+// Cross product of the xyz lanes, the w lane comes out as 0
+fn cross(a: F32x4, b: F32x4) -> F32x4 {
+    a.shuffle::<YZXW>()
+        .mul(b.shuffle::<ZXYW>())
+        .sub(a.shuffle::<ZXYW>().mul(b.shuffle::<YZXW>()))
+}
+
+fn dot(a: F32x4, b: F32x4) -> f32 {
+    a.mul(b).sum()
+}
+
+pub(crate) fn vec4_dot(a: &Vec4, b: &Vec4) -> f32 {
+    dot(F32x4::from_vec4(a), F32x4::from_vec4(b))
+}
+
+pub(crate) fn quat_dot(a: &Quat, b: &Quat) -> f32 {
+    dot(F32x4::from_quat(a), F32x4::from_quat(b))
+}
+
+pub(crate) fn mat4_transpose(m: &Mat4) -> Mat4 {
+    from_rows(F32x4::transpose(rows(m)))
+}
+
+pub(crate) fn mat4_mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    // Row i of the product is sum_k a[i][k] * row k of `b`
+    let b = rows(b);
+    let row = |r: &Vec4| {
+        b[0].mul(F32x4::splat(r.x))
+            .add(b[1].mul(F32x4::splat(r.y)))
+            .add(b[2].mul(F32x4::splat(r.z)))
+            .add(b[3].mul(F32x4::splat(r.w)))
+    };
+    from_rows([row(&a.a), row(&a.b), row(&a.c), row(&a.d)])
+}
+
+pub(crate) fn mat4_mul_vec4(m: &Mat4, v: &Vec4) -> Vec4 {
+    // Multiply every row by `v`, then sum the products across rows at once
+    let v = F32x4::from_vec4(v);
+    let [r0, r1, r2, r3] = rows(m).map(|r| r.mul(v));
+    let [c0, c1, c2, c3] = F32x4::transpose([r0, r1, r2, r3]);
+    c0.add(c1).add(c2.add(c3)).to_vec4()
+}
+
+pub(crate) fn mat4_invert(m: &Mat4) -> Option<Mat4> {
+    // Same derivation as `Mat4::adjugate`, carrying xyz in the lanes
+    let [a, b, c, d] = rows(m);
+    let (x, y, z, w) = (m.a.w, m.b.w, m.c.w, m.d.w);
+
+    let s = cross(a, b);
+    let t = cross(c, d);
+    let u = a.mul(F32x4::splat(y)).sub(b.mul(F32x4::splat(x)));
+    let v = c.mul(F32x4::splat(w)).sub(d.mul(F32x4::splat(z)));
+
+    let det = dot(s, v) + dot(t, u);
+    let mut norms = F32x4::transpose([a, b, c, d]).map(|col| dot(col, col).sqrt());
+    if m.is_affine() {
+        norms[3] = 1.;
+    }
+    if !is_invertible(det, &norms) {
+        return None;
+    }
+
+    // The xyz lanes of `s`, `t`, `u` and `v` are 3D vectors with w = 0, so
+    // their 4-lane dot products are the 3D ones
+    let a3 = a.mul(F32x4::new(1., 1., 1., 0.));
+    let b3 = b.mul(F32x4::new(1., 1., 1., 0.));
+    let c3 = c.mul(F32x4::new(1., 1., 1., 0.));
+    let d3 = d.mul(F32x4::new(1., 1., 1., 0.));
+    let lane_w = |s: f32| F32x4::new(0., 0., 0., s);
+
+    let r0 = cross(b3, v)
+        .add(t.mul(F32x4::splat(y)))
+        .add(lane_w(-dot(b3, t)));
+    let r1 = cross(v, a3)
+        .sub(t.mul(F32x4::splat(x)))
+        .add(lane_w(dot(a3, t)));
+    let r2 = cross(d3, u)
+        .add(s.mul(F32x4::splat(w)))
+        .add(lane_w(-dot(d3, s)));
+    let r3 = cross(u, c3)
+        .sub(s.mul(F32x4::splat(z)))
+        .add(lane_w(dot(c3, s)));
+
+    let inv_det = F32x4::splat(1. / det);
+    Some(from_rows(
+        F32x4::transpose([r0, r1, r2, r3]).map(|r| r.mul(inv_det)),
+    ))
+}
+
+pub(crate) fn quat_mul(lhs: &Quat, rhs: &Quat) -> Quat {
+    // Expand the product by the components of `lhs`
+    let q = F32x4::from_quat(rhs);
+    let wzyx = q.shuffle::<{ shuffle_mask(3, 2, 1, 0) }>();
+    let zwxy = q.shuffle::<{ shuffle_mask(2, 3, 0, 1) }>();
+    let yxwz = q.shuffle::<{ shuffle_mask(1, 0, 3, 2) }>();
+
+    q.mul(F32x4::splat(lhs.w))
+        .add(wzyx.mul(F32x4::new(lhs.x, -lhs.x, lhs.x, -lhs.x)))
+        .add(zwxy.mul(F32x4::new(lhs.y, lhs.y, -lhs.y, -lhs.y)))
+        .add(yxwz.mul(F32x4::new(-lhs.z, lhs.z, lhs.z, -lhs.z)))
+        .to_quat()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dmat4::DMat4,
+        dquat::DQuat,
+        dvec4::DVec4,
+        structure::{Mat, SquareMat, VecSpace},
+        vec3::Vec3,
+    };
+
+    use super::*;
+
+    // The f64 types run the original scalar code, so they serve as reference
+
+    fn sample() -> Mat4 {
+        [
+            [6., 1., 2., 4.],
+            [4., 3., 3., 2.],
+            [2., 5., 5., 6.],
+            [8., 7., 6., 3.],
+        ]
+        .into()
+    }
+
+    #[test]
+    fn multiplies_like_scalar_code() {
+        let a = sample();
+        let b = Mat4::perspective_rh(1.2, 1.5, 0.1, 100.);
+        let expected = (DMat4::from(a) * DMat4::from(b)).as_f32();
+        assert_eq!(mat4_mul(&a, &b), expected);
+    }
+
+    #[test]
+    fn transforms_like_scalar_code() {
+        let m = sample();
+        let v = Vec4::new(1., -2., 0.5, 1.);
+        let expected = (DMat4::from(m) * DVec4::from(v)).as_f32();
+        assert_eq!(mat4_mul_vec4(&m, &v), expected);
+    }
+
+    #[test]
+    fn inverts_like_scalar_code() {
+        let m = sample();
+        let expected = DMat4::from(m).invert().unwrap().as_f32();
+        assert_eq!(mat4_invert(&m).unwrap(), expected);
+        assert!(mat4_invert(&Mat4::ZERO).is_none());
+    }
+
+    #[test]
+    fn transposes_like_scalar_code() {
+        let m = sample();
+        assert_eq!(mat4_transpose(&m), DMat4::from(m).transpose().as_f32());
+    }
+
+    #[test]
+    fn multiplies_quats_like_scalar_code() {
+        let a = Quat::from_axis_angle(Vec3::new(1., 2., 3.).normalize(), 0.8);
+        let b = Quat::new(-0.5, 0.25, 2., 1.);
+        let expected = (DQuat::from(a) * DQuat::from(b)).as_f32();
+        assert_eq!(quat_mul(&a, &b), expected);
+        assert!(float_cmp::approx_eq!(
+            f32,
+            quat_dot(&a, &b),
+            a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w,
+            crate::precision::PRECISION
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    mat3::Mat3,
+    mat4::Mat4,
+    pt3::Pt3,
+    structure::{Mat, VecSpace},
+    transform4::T4Mul,
+    vec3::Vec3,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sphere {
+    pub center: Pt3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub fn new(center: Pt3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Bounding sphere of `points` with Ritter's algorithm, at most about 5%
+    /// larger than the minimal one. `None` when there are no points.
+    pub fn from_points(points: &[Pt3]) -> Option<Self> {
+        let first = points.first()?;
+
+        // Start from the most separated pair of axis-extreme points
+        let mut extremes = [*first; 6];
+        for p in points {
+            for k in 0..3 {
+                if p[k] < extremes[2 * k][k] {
+                    extremes[2 * k] = *p;
+                }
+                if p[k] > extremes[2 * k + 1][k] {
+                    extremes[2 * k + 1] = *p;
+                }
+            }
+        }
+        let (a, b) = (0..3)
+            .map(|k| (extremes[2 * k], extremes[2 * k + 1]))
+            .max_by(|(a, b), (c, d)| (*b - *a).norm2().total_cmp(&(*d - *c).norm2()))
+            .unwrap();
+
+        let start = Self::new((a + b) * 0.5, (b - a).norm() * 0.5);
+        Some(points.iter().fold(start, |s, p| s.include(p)))
+    }
+
+    /// Smallest sphere containing this sphere and `p`.
+    pub fn include(&self, p: &Pt3) -> Self {
+        self.union(&Self::new(*p, 0.))
+    }
+
+    /// Smallest sphere containing both spheres.
+    pub fn union(&self, other: &Sphere) -> Self {
+        let d = Vec3::from(other.center - self.center);
+        let dist = d.norm();
+        if dist + other.radius <= self.radius {
+            return *self;
+        }
+        if dist + self.radius <= other.radius {
+            return *other;
+        }
+        let radius = (dist + self.radius + other.radius) * 0.5;
+        Self::new(self.center + d * ((radius - self.radius) / dist), radius)
+    }
+
+    /// Grows the radius by `margin`, shrinking for negative values.
+    pub fn expand(&self, margin: f32) -> Self {
+        Self::new(self.center, self.radius + margin)
+    }
+
+    pub fn contains_point(&self, p: &Pt3) -> bool {
+        (*p - self.center).norm2() <= self.radius * self.radius
+    }
+
+    pub fn contains(&self, other: &Sphere) -> bool {
+        (other.center - self.center).norm() + other.radius <= self.radius
+    }
+
+    pub fn overlaps(&self, other: &Sphere) -> bool {
+        let r = self.radius + other.radius;
+        (other.center - self.center).norm2() <= r * r
+    }
+
+    /// Sphere around the affine image of this sphere. The radius is scaled by
+    /// the largest singular value of the linear part, so the result is tight
+    /// for uniform scales and conservative otherwise.
+    pub fn transform(&self, m: &Mat4) -> Self {
+        let l = Mat3::from(m);
+        let (stretch, _) = (l.transpose() * l).symmetric_eigen();
+        let s = stretch.max_element().sqrt();
+        Self::new(T4Mul::mul(m, &self.center), self.radius * s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use crate::{
+        assert_approx_eq, precision::PRECISION, structure::SquareMat, transform4::Transform4,
+    };
+
+    use super::*;
+
+    #[test]
+    fn bounds_points() {
+        let points = [
+            Pt3::new(1., 0., 0.),
+            Pt3::new(-1., 0., 0.),
+            Pt3::new(0., 1., 0.),
+            Pt3::new(0., 0., -1.),
+            Pt3::new(0.5, 0.5, 0.5),
+        ];
+        let s = Sphere::from_points(&points).unwrap();
+        for p in &points {
+            assert!(s.expand(1e-5).contains_point(p));
+        }
+        assert!(s.radius < 1.05);
+        assert!(Sphere::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn merges() {
+        let a = Sphere::new(Pt3::new(0., 0., 0.), 1.);
+        let b = Sphere::new(Pt3::new(4., 0., 0.), 1.);
+        let u = a.union(&b);
+        assert_approx_eq!(u.center, Pt3::new(2., 0., 0.));
+        assert!(approx_eq!(f32, u.radius, 3., PRECISION));
+        assert!(u.expand(1e-5).contains(&a) && u.expand(1e-5).contains(&b));
+        assert!(!a.overlaps(&b));
+
+        let inner = Sphere::new(Pt3::new(0.5, 0., 0.), 0.25);
+        assert_approx_eq!(a.union(&inner).center, Pt3::new(0., 0., 0.));
+        assert!(a
+            .include(&Pt3::new(3., 0., 0.))
+            .contains_point(&Pt3::new(2.9, 0., 0.)));
+    }
+
+    #[test]
+    fn transforms() {
+        let s = Sphere::new(Pt3::new(1., 0., 0.), 1.);
+        let mut m = Mat4::from_diagonal([1., 3., 2., 1.].into());
+        m.set_translation(Vec3::new(0., 1., 0.));
+        let t = s.transform(&m);
+        assert_approx_eq!(t.center, Pt3::new(1., 1., 0.));
+        assert!(approx_eq!(f32, t.radius, 3., PRECISION));
+    }
+}
@@ -2,8 +2,13 @@ use std::ops::{
     Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
 };
 
+use crate::scalar::Scalar;
+
+pub type ScalarOf<V> = <V as EuclideanSpace<V>>::Scalar;
+
 pub trait EuclideanSpace<V: VecSpace> {
-    fn dot(&self, other: &V) -> f32;
+    type Scalar: Scalar;
+    fn dot(&self, other: &V) -> Self::Scalar;
 }
 
 pub trait VecSpace
@@ -13,15 +18,15 @@ where
     Self: Index<usize> + IndexMut<usize>,
     Self: Add<Output = Self> + AddAssign,
     Self: Sub<Output = Self> + SubAssign,
-    Self: Mul<f32, Output = Self> + MulAssign<f32>,
-    Self: Div<f32, Output = Self> + DivAssign<f32>,
+    Self: Mul<ScalarOf<Self>, Output = Self> + MulAssign<ScalarOf<Self>>,
+    Self: Div<ScalarOf<Self>, Output = Self> + DivAssign<ScalarOf<Self>>,
     Self: Neg<Output = Self>,
 {
-    fn norm2(&self) -> f32 {
+    fn norm2(&self) -> ScalarOf<Self> {
         self.dot(self)
     }
 
-    fn norm(&self) -> f32 {
+    fn norm(&self) -> ScalarOf<Self> {
         self.norm2().sqrt()
     }
 
@@ -44,6 +49,7 @@ where
     Self: Index<usize, Output = Self::Column>,
     Self: IndexMut<usize, Output = Self::Column>,
 {
+    type Scalar: Scalar;
     type Row;
     type Column;
     type Transpose: Mat<Row = Self::Column, Column = Self::Row>;
@@ -60,6 +66,30 @@ where
     type RowColumn;
     const IDENTITY: Self;
     fn from_diagonal(d: Self::RowColumn) -> Self;
-    fn determinant(&self) -> f32;
+    fn determinant(&self) -> Self::Scalar;
+    fn adjugate(&self) -> Self;
     fn invert(&self) -> Option<Self>;
+
+    /// Largest absolute column sum, the induced 1-norm of the matrix.
+    fn norm_1(&self) -> Self::Scalar;
+
+    fn cofactor(&self) -> Self {
+        self.adjugate().transpose()
+    }
+
+    fn invert_or_identity(&self) -> Self {
+        self.invert().unwrap_or(Self::IDENTITY)
+    }
+
+    /// Estimates the 1-norm condition number `|M| |M^-1|`.
+    ///
+    /// Returns infinity for matrices that `invert` rejects. Values above
+    /// roughly `1e6` mean an inverse computed in `f32` has lost most of its
+    /// significant digits.
+    fn condition(&self) -> Self::Scalar {
+        match self.invert() {
+            Some(inv) => self.norm_1() * inv.norm_1(),
+            None => Self::Scalar::INFINITY,
+        }
+    }
 }
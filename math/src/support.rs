@@ -0,0 +1,254 @@
+use crate::{
+    aabb::Aabb,
+    capsule::Capsule,
+    gjk::{self, Contact, Separation},
+    mat3::Mat3,
+    mat4::Mat4,
+    obb::Obb,
+    pt3::Pt3,
+    quat::Quat,
+    sphere::Sphere,
+    structure::{EuclideanSpace, Mat, VecSpace},
+    transform4::Transform4,
+    triangle::Triangle,
+    vec3::Vec3,
+};
+
+/// Convex shape described by its support mapping, the basis of the GJK and
+/// EPA queries.
+pub trait Support {
+    /// Point of the shape furthest along `dir`, which need not be
+    /// normalized. Any point of the shape is valid for a zero `dir`.
+    fn support(&self, dir: &Vec3) -> Pt3;
+
+    fn intersects<S: Support>(&self, other: &S) -> bool
+    where
+        Self: Sized,
+    {
+        gjk::intersects(self, other)
+    }
+
+    /// Closest points between two disjoint shapes, `None` when they overlap.
+    fn separation<S: Support>(&self, other: &S) -> Option<Separation>
+    where
+        Self: Sized,
+    {
+        gjk::separation(self, other)
+    }
+
+    /// Penetration depth and normal of two overlapping shapes, `None` when
+    /// they are disjoint or their overlap has no volume.
+    fn penetration<S: Support>(&self, other: &S) -> Option<Contact>
+    where
+        Self: Sized,
+    {
+        gjk::penetration(self, other)
+    }
+}
+
+impl<T: Support + ?Sized> Support for &T {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        (**self).support(dir)
+    }
+}
+
+impl Support for Pt3 {
+    fn support(&self, _: &Vec3) -> Pt3 {
+        *self
+    }
+}
+
+impl Support for Sphere {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        let n = dir.norm();
+        if n > 0. {
+            self.center + *dir * (self.radius / n)
+        } else {
+            self.center
+        }
+    }
+}
+
+impl Support for Aabb {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        let pick = |k: usize| {
+            if dir[k] < 0. {
+                self.min[k]
+            } else {
+                self.max[k]
+            }
+        };
+        Pt3::new(pick(0), pick(1), pick(2))
+    }
+}
+
+impl Support for Obb {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        (0..3).fold(self.center, |p, k| {
+            let axis = self.axes[k];
+            let h = self.half_extents[k];
+            p + if axis.dot(dir) < 0. {
+                -axis * h
+            } else {
+                axis * h
+            }
+        })
+    }
+}
+
+impl Support for Capsule {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        let end = if Vec3::from(self.b - self.a).dot(dir) > 0. {
+            self.b
+        } else {
+            self.a
+        };
+        Sphere::new(end, self.radius).support(dir)
+    }
+}
+
+impl Support for Triangle {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        furthest(&self.vertices(), dir)
+    }
+}
+
+fn furthest(points: &[Pt3], dir: &Vec3) -> Pt3 {
+    *points
+        .iter()
+        .max_by(|p, q| {
+            Vec3::from(**p)
+                .dot(dir)
+                .total_cmp(&Vec3::from(**q).dot(dir))
+        })
+        .expect("support of an empty point set")
+}
+
+/// Convex hull of a point cloud, which need not be reduced to the hull's
+/// vertices.
+#[derive(Clone, Debug)]
+pub struct ConvexHull {
+    pub points: Vec<Pt3>,
+}
+
+impl ConvexHull {
+    /// `None` when there are no points.
+    pub fn new(points: Vec<Pt3>) -> Option<Self> {
+        if points.is_empty() {
+            None
+        } else {
+            Some(Self { points })
+        }
+    }
+}
+
+impl Support for ConvexHull {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        furthest(&self.points, dir)
+    }
+}
+
+/// Points `a + b` for `a` in the first shape and `b` in the second, e.g. a
+/// box swept by a sphere gives a rounded box.
+#[derive(Clone, Copy, Debug)]
+pub struct MinkowskiSum<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Support, B: Support> MinkowskiSum<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A: Support, B: Support> Support for MinkowskiSum<A, B> {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        self.a.support(dir) + self.b.support(dir)
+    }
+}
+
+/// Shape mapped by an affine transform, which may scale and shear it.
+#[derive(Clone, Copy, Debug)]
+pub struct Transformed<S> {
+    pub shape: S,
+    pub linear: Mat3,
+    pub translation: Vec3,
+}
+
+impl<S: Support> Transformed<S> {
+    pub fn new(shape: S, m: &Mat4) -> Self {
+        Self {
+            shape,
+            linear: Mat3::from(m),
+            translation: m.get_translation(),
+        }
+    }
+
+    pub fn from_rotation_translation(shape: S, rotation: &Quat, translation: Vec3) -> Self {
+        Self {
+            shape,
+            linear: Mat3::from(rotation),
+            translation,
+        }
+    }
+}
+
+impl<S: Support> Support for Transformed<S> {
+    fn support(&self, dir: &Vec3) -> Pt3 {
+        // The support of M * S along d is M applied to the support of S
+        // along M^T * d
+        let local = self.shape.support(&(self.linear.transpose() * *dir));
+        Pt3::from(self.linear * Vec3::from(local)) + self.translation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use float_cmp::approx_eq;
+
+    use crate::{assert_approx_eq, precision::PRECISION, structure::SquareMat};
+
+    use super::*;
+
+    #[test]
+    fn maps_primitives() {
+        let d = Vec3::new(1., -2., 0.5);
+        let s = Sphere::new(Pt3::new(1., 0., 0.), 3.);
+        assert_approx_eq!(s.support(&d), s.center + d.normalize() * 3.);
+        assert_approx_eq!(s.support(&Vec3::ZERO), s.center);
+
+        let b = Aabb::new(Pt3::new(-1., -2., -3.), Pt3::new(1., 2., 3.));
+        assert_approx_eq!(b.support(&d), Pt3::new(1., -2., 3.));
+        assert_approx_eq!(Obb::from_aabb(&b).support(&d), Pt3::new(1., -2., 3.));
+
+        let c = Capsule::new(Pt3::new(0., 0., 0.), Pt3::new(0., 4., 0.), 1.);
+        assert_approx_eq!(c.support(&Vec3::Y), Pt3::new(0., 5., 0.));
+        assert_approx_eq!(c.support(&-Vec3::X), Pt3::new(-1., 0., 0.));
+
+        let hull = ConvexHull::new(b.corners().to_vec()).unwrap();
+        assert_approx_eq!(hull.support(&d), Pt3::new(1., -2., 3.));
+        assert!(ConvexHull::new(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn combines_shapes() {
+        let b = Aabb::new(Pt3::new(-1., -1., -1.), Pt3::new(1., 1., 1.));
+        let rounded = MinkowskiSum::new(b, Sphere::new(Pt3::new(0., 0., 0.), 0.5));
+        assert_approx_eq!(rounded.support(&Vec3::X), Pt3::new(1.5, 1., 1.));
+
+        let mut m =
+            Mat4::from(Mat3::from_angle_z(FRAC_PI_2) * Mat3::from_diagonal(Vec3::new(2., 1., 1.)));
+        m.set_translation(Vec3::new(0., 0., 10.));
+        let t = Transformed::new(b, &m);
+        // Stretched along x then turned onto y
+        assert!(approx_eq!(f32, t.support(&Vec3::Y).y, 2., PRECISION));
+        assert!(approx_eq!(f32, t.support(&Vec3::Z).z, 11., PRECISION));
+
+        let q = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        let r = Transformed::from_rotation_translation(&b, &q, Vec3::new(5., 0., 0.));
+        assert_approx_eq!(r.support(&Vec3::new(1., 1., 1.)), Pt3::new(6., 1., 1.));
+    }
+}
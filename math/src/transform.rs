@@ -0,0 +1,378 @@
+use std::ops;
+
+use crate::{
+    affine3::Affine3,
+    approx::ApproxEq,
+    impl_conversions, impl_op,
+    mat3::Mat3,
+    mat4::Mat4,
+    pt3::Pt3,
+    quat::Quat,
+    scalar::Scalar,
+    structure::{SquareMat, VecSpace},
+    transform4::Transform4,
+    vec3::Vec3,
+};
+
+/// Scale, then rotation, then translation, the form scene hierarchies and
+/// animation channels are authored in. Maps `p` to `t + R * (s * p)`.
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3 {
+            x: 1.,
+            y: 1.,
+            z: 1.,
+        },
+    };
+
+    pub fn new(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Self {
+            rotation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Linear part `R * S`.
+    pub fn linear(&self) -> Mat3 {
+        Mat3::from(self.rotation) * Mat3::from_diagonal(self.scale)
+    }
+
+    pub fn transform_point(&self, p: &Pt3) -> Pt3 {
+        (self.transform_vector(&Vec3::from(p)) + self.translation).into()
+    }
+
+    /// Maps a direction, ignoring the translation.
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        self.rotation.rotate(&(v * self.scale))
+    }
+
+    /// Whether the scale is the same along every axis, up to a relative
+    /// `f32::DEFAULT_MAX_RELATIVE`. A uniform scale commutes with every
+    /// rotation, so `inverse` and `compose` always have a TRS result then.
+    pub fn has_uniform_scale(&self) -> bool {
+        let s = self.scale;
+        let max_relative = f32::DEFAULT_MAX_RELATIVE;
+        Vec3::new(s.x, s.x, s.x).relative_eq(&s, 0., max_relative)
+    }
+
+    /// `None` when the scale has a zero component, or when the inverse
+    /// rotation does not commute with the scale. The inverse then applies
+    /// the scale after the rotation, which TRS cannot hold; invert
+    /// `Affine3::from(t)` instead.
+    pub fn inverse(&self) -> Option<Self> {
+        let rotation = self.rotation.inverse();
+        if self.scale.abs().min_element() == 0. || !commutes(&rotation, &self.scale) {
+            return None;
+        }
+        let scale = Vec3::new(1., 1., 1.) / self.scale;
+        let translation = -(rotation.rotate(&self.translation) * scale);
+        Some(Self::new(translation, rotation, scale))
+    }
+
+    /// `self` applied after `child`, as a parent node over a child in a
+    /// scene hierarchy. `None` when the child rotation does not commute
+    /// with the parent scale, the product then has a shear that TRS cannot
+    /// hold; compose the `Affine3` forms instead.
+    pub fn compose(&self, child: &Transform) -> Option<Self> {
+        if !commutes(&child.rotation, &self.scale) {
+            return None;
+        }
+        Some(Self::new(
+            self.transform_point(&Pt3::from(child.translation)).into(),
+            self.rotation * child.rotation,
+            self.scale * child.scale,
+        ))
+    }
+
+    /// Interpolates translation and scale linearly and rotation with
+    /// `Quat::slerp`, as animation sampling does between keyframes.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            self.translation.lerp(&other.translation, t),
+            self.rotation.slerp(&other.rotation, t),
+            self.scale.lerp(&other.scale, t),
+        )
+    }
+}
+
+// Whether `R * S == S * R`, which holds for a uniform scale and for
+// rotations that only mix axes sharing the same scale factor. The tolerance
+// is relative to the largest scale factor.
+fn commutes(rotation: &Quat, scale: &Vec3) -> bool {
+    let r = Mat3::from(rotation);
+    let s = Mat3::from_diagonal(*scale);
+    let epsilon = scale.abs().max_element() * f32::DEFAULT_MAX_RELATIVE;
+    (r * s).abs_diff_eq(&(s * r), epsilon)
+}
+
+impl_op!(Transform : Pt3 => Pt3, ops::Mul { fn mul |m: &Transform, p: &Pt3| {
+    m.transform_point(p)
+}});
+
+impl_op!(Transform : Vec3 => Vec3, ops::Mul { fn mul |m: &Transform, v: &Vec3| {
+    m.transform_vector(v)
+}});
+
+impl_conversions!(Transform => (Vec3, Quat, Vec3), |m: &Transform| {
+    (m.translation, m.rotation, m.scale)
+});
+
+impl_conversions!((Vec3, Quat, Vec3) => Transform, |(t, r, s): &(Vec3, Quat, Vec3)| {
+    Transform::new(*t, *r, *s)
+});
+
+impl_conversions!(Transform => Affine3, |m: &Transform| {
+    Affine3::new(&m.linear(), m.translation)
+});
+
+impl_conversions!(Transform => Mat4, |m: &Transform| {
+    Mat4::from(Affine3::from(m))
+});
+
+impl Mat4 {
+    /// Splits an affine matrix into `T * R * S`, dropping any shear. Mirrors
+    /// show up as a negative `scale.x`. `None` when the matrix is projective
+    /// or singular.
+    pub fn decompose(&self) -> Option<Transform> {
+        self.decompose_with_shear().map(|(t, _)| t)
+    }
+
+    /// Splits an affine matrix into `T * R * K * S` where the shear `K` has a
+    /// unit diagonal and `K * S` is the symmetric stretch of the polar
+    /// decomposition. `None` when the matrix is projective or singular.
+    pub fn decompose_with_shear(&self) -> Option<(Transform, Mat3)> {
+        if !self.is_affine() {
+            return None;
+        }
+        let (mut q, mut s) = Mat3::from(self).polar_decompose()?;
+        if q.determinant() < 0. {
+            // Move the reflection out of the rotation into the x scale, the
+            // flipped axis is arbitrary
+            let flip = Mat3::from_diagonal(Vec3::new(-1., 1., 1.));
+            q *= flip;
+            s = flip * s;
+        }
+        let scale = Vec3::new(s[0][0], s[1][1], s[2][2]);
+        let shear = s * Mat3::from_diagonal(Vec3::new(1., 1., 1.) / scale);
+        let rotation = Quat::from(q).normalize();
+        Some((
+            Transform::new(self.get_translation(), rotation, scale),
+            shear,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::assert_approx_eq;
+
+    use super::*;
+
+    fn sample() -> Transform {
+        Transform::new(
+            Vec3::new(1., -2., 3.),
+            Quat::from_axis_angle(Vec3::new(1., 2., -1.).normalize(), 0.9),
+            Vec3::new(2., 0.5, 3.),
+        )
+    }
+
+    #[test]
+    fn transforms_points_like_its_matrix() {
+        let m = sample();
+        let p = Pt3::new(0.5, 4., -1.);
+        assert_approx_eq!(m * p, Affine3::from(m) * p);
+        assert_eq!(m * Vec3::X, Mat3::from(Mat4::from(m)) * Vec3::X);
+
+        let turn = Transform::from_rotation(Quat::from_axis_angle(Vec3::Z, FRAC_PI_2));
+        assert_approx_eq!(turn * Pt3::new(1., 0., 0.), Pt3::new(0., 1., 0.));
+        assert_approx_eq!(
+            Transform::IDENTITY * Pt3::new(4., 5., 6.),
+            Pt3::new(4., 5., 6.)
+        );
+    }
+
+    #[test]
+    fn composes_and_inverts() {
+        let parent = Transform::new(
+            Vec3::new(-1., 0., 5.),
+            Quat::from_axis_angle(Vec3::X, 0.3),
+            Vec3::new(2., 2., 2.),
+        );
+        let child = sample();
+        let p = Pt3::new(0.5, -2., 7.);
+        let composed = parent.compose(&child).unwrap();
+        assert_approx_eq!(composed * p, parent * (child * p));
+        assert_eq!(Mat4::from(composed), Mat4::from(parent) * Mat4::from(child));
+
+        let inv = parent.inverse().unwrap();
+        assert_approx_eq!(inv * (parent * p), p);
+        assert_eq!(Mat4::from(inv.compose(&parent).unwrap()), Mat4::IDENTITY);
+        assert!(Transform::from_scale(Vec3::new(0., 0., 0.))
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn inverts_and_composes_commuting_non_uniform_scales() {
+        let p = Pt3::new(0.5, 4., -1.);
+        let stretch = Transform::from_scale(Vec3::new(2., 1., 1.));
+        let inv = stretch.inverse().unwrap();
+        assert_approx_eq!(inv * (stretch * p), p);
+
+        // Turning about the stretched axis, or within the plane of equal
+        // factors, keeps the scale on the same axes
+        let about_x = Transform::new(
+            Vec3::new(1., 2., 3.),
+            Quat::from_axis_angle(Vec3::X, 0.7),
+            Vec3::new(2., 1., 1.),
+        );
+        let inv = about_x.inverse().unwrap();
+        assert_approx_eq!(inv * (about_x * p), p);
+        assert_eq!(
+            Mat4::from(inv),
+            Transform4::invert(&Mat4::from(about_x)).unwrap()
+        );
+        let quarter = Transform::from_rotation(Quat::from_axis_angle(Vec3::Z, FRAC_PI_2));
+        assert!(Transform::from_scale(Vec3::new(3., 3., 1.))
+            .compose(&quarter)
+            .is_some());
+
+        // A non-uniform parent over an unrotated or axis-turned child
+        let parent = Transform::new(
+            Vec3::new(-1., 0., 5.),
+            Quat::from_axis_angle(Vec3::Y, 0.3),
+            Vec3::new(2., 1., 1.),
+        );
+        for child in [
+            Transform::new(
+                Vec3::new(1., 2., 3.),
+                Quat::IDENTITY,
+                Vec3::new(1., 3., 0.5),
+            ),
+            about_x,
+        ] {
+            let composed = parent.compose(&child).unwrap();
+            assert_approx_eq!(composed * p, parent * (child * p));
+            assert_eq!(Mat4::from(composed), Mat4::from(parent) * Mat4::from(child));
+        }
+        assert!(Transform::from_scale(Vec3::new(0., 1., 1.))
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn refuses_inexact_non_uniform_results() {
+        // A rotated non-uniform scale has no TRS inverse and a parent with
+        // one shears its children, the affine forms stay exact
+        let t = sample();
+        assert!(!t.has_uniform_scale());
+        assert!(t.inverse().is_none());
+        assert!(t.compose(&sample()).is_none());
+        assert!(Transform::from_scale(Vec3::new(2., 1., 1.))
+            .compose(&Transform::from_rotation(sample().rotation))
+            .is_none());
+
+        let p = Pt3::new(0.5, 4., -1.);
+        let inv = Affine3::from(t).inverse().unwrap();
+        assert_approx_eq!(inv * (t * p), p);
+        assert_eq!(Mat4::from(inv), Transform4::invert(&Mat4::from(t)).unwrap());
+        let product = Affine3::from(t) * Affine3::from(sample());
+        assert_approx_eq!(product * p, t * (sample() * p));
+    }
+
+    #[test]
+    fn interpolates() {
+        let a = Transform::IDENTITY;
+        let b = Transform::new(
+            Vec3::new(2., 0., -4.),
+            Quat::from_axis_angle(Vec3::Y, FRAC_PI_2),
+            Vec3::new(3., 1., 1.),
+        );
+        assert_eq!(Mat4::from(a.lerp(&b, 0.)), Mat4::from(a));
+        assert_eq!(Mat4::from(a.lerp(&b, 1.)), Mat4::from(b));
+
+        let mid = a.lerp(&b, 0.5);
+        assert_eq!(mid.translation, Vec3::new(1., 0., -2.));
+        assert_eq!(mid.scale, Vec3::new(2., 1., 1.));
+        assert_eq!(
+            mid.rotation.rotate(&Vec3::Z),
+            Quat::from_axis_angle(Vec3::Y, FRAC_PI_2 * 0.5).rotate(&Vec3::Z)
+        );
+    }
+
+    #[test]
+    fn decomposes_matrices() {
+        let m = sample();
+        let d = Mat4::from(m).decompose().unwrap();
+        assert_eq!(d.translation, m.translation);
+        assert_eq!(d.scale, m.scale);
+        assert_eq!(Mat4::from(d), Mat4::from(m));
+
+        // A mirror is reported as a negative scale
+        let mirrored = Transform::new(m.translation, m.rotation, Vec3::new(2., -0.5, 3.));
+        let d = Mat4::from(mirrored).decompose().unwrap();
+        assert!(d.scale.x < 0.);
+        assert_eq!(Mat4::from(d), Mat4::from(mirrored));
+
+        assert!(Mat4::perspective_rh(1., 1., 0.1, 10.).decompose().is_none());
+        assert!(Mat4::from(Transform::from_scale(Vec3::new(1., 0., 1.)))
+            .decompose()
+            .is_none());
+    }
+
+    #[test]
+    fn separates_shear() {
+        let stretch = Mat3::new(
+            1.5, 0.4, 0., //
+            0.4, 2., 0.2, //
+            0., 0.2, 0.5,
+        );
+        let rotation = Quat::from_axis_angle(Vec3::new(0., 1., 1.).normalize(), -0.8);
+        let mut h = Mat4::from(Mat3::from(rotation) * stretch);
+        h.set_translation(Vec3::new(3., 2., 1.));
+
+        let (t, k) = h.decompose_with_shear().unwrap();
+        assert_eq!(t.scale, Vec3::new(1.5, 2., 0.5));
+        assert_eq!(
+            t.rotation.rotate(&Vec3::new(1., 2., 3.)),
+            rotation.rotate(&Vec3::new(1., 2., 3.))
+        );
+        assert_eq!(Vec3::new(k[0][0], k[1][1], k[2][2]), Vec3::new(1., 1., 1.));
+        let mut back = Mat4::from(Mat3::from(t.rotation) * k * Mat3::from_diagonal(t.scale));
+        back.set_translation(t.translation);
+        assert_eq!(back, h);
+    }
+}
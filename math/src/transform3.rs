@@ -0,0 +1,169 @@
+use crate::{
+    mat3::Mat3, precision::is_invertible, pt2::Pt2, structure::VecSpace, vec2::Vec2, vec3::Vec3,
+};
+
+// 2D affine transforms stored in a `Mat3` whose last row is (0, 0, 1),
+// mirroring `Transform4` for 3D.
+
+pub trait Transform3
+where
+    Self: Clone,
+{
+    fn new(c0r0: f32, c1r0: f32, c2r0: f32, c0r1: f32, c1r1: f32, c2r1: f32) -> Self;
+
+    fn from_columns(a: Vec2, b: Vec2, p: Vec2) -> Self;
+
+    fn get_translation(&self) -> Vec2;
+
+    fn set_translation(&mut self, t: Vec2);
+
+    fn invert(&self) -> Option<Self>;
+}
+
+pub trait T3Mul<Rhs = Self>
+where
+    Self: Transform3,
+{
+    fn mul(&self, rhs: &Rhs) -> Rhs;
+}
+
+impl Transform3 for Mat3 {
+    fn new(c0r0: f32, c1r0: f32, c2r0: f32, c0r1: f32, c1r1: f32, c2r1: f32) -> Self {
+        Self::from_columns(
+            [c0r0, c1r0, c2r0].into(),
+            [c0r1, c1r1, c2r1].into(),
+            Vec3::Z,
+        )
+    }
+
+    fn from_columns(a: Vec2, b: Vec2, p: Vec2) -> Self {
+        Transform3::new(
+            a.x, b.x, p.x, //
+            a.y, b.y, p.y,
+        )
+    }
+
+    fn get_translation(&self) -> Vec2 {
+        Vec2::new(self[0][2], self[1][2])
+    }
+
+    fn set_translation(&mut self, t: Vec2) {
+        self[0][2] = t.x;
+        self[1][2] = t.y;
+    }
+
+    fn invert(&self) -> Option<Self> {
+        let m = self;
+        let a = Vec2::new(m[0][0], m[1][0]);
+        let b = Vec2::new(m[0][1], m[1][1]);
+        let p = self.get_translation();
+
+        let det = a.perp_dot(&b);
+
+        if is_invertible(det, &[a.norm(), b.norm()]) {
+            let inv_det = 1. / det;
+            let r0 = Vec2::new(b.y, -b.x) * inv_det;
+            let r1 = Vec2::new(-a.y, a.x) * inv_det;
+            Some(Transform3::new(
+                r0.x,
+                r0.y,
+                -(r0.x * p.x + r0.y * p.y), //
+                r1.x,
+                r1.y,
+                -(r1.x * p.x + r1.y * p.y),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl T3Mul for Mat3 {
+    fn mul(&self, b: &Self) -> Self {
+        let a = self;
+        Transform3::new(
+            a[0][0] * b[0][0] + a[0][1] * b[1][0],
+            a[0][0] * b[0][1] + a[0][1] * b[1][1],
+            a[0][0] * b[0][2] + a[0][1] * b[1][2] + a[0][2],
+            a[1][0] * b[0][0] + a[1][1] * b[1][0],
+            a[1][0] * b[0][1] + a[1][1] * b[1][1],
+            a[1][0] * b[0][2] + a[1][1] * b[1][2] + a[1][2],
+        )
+    }
+}
+
+impl T3Mul<Pt2> for Mat3 {
+    fn mul(&self, p: &Pt2) -> Pt2 {
+        let m = self;
+        Pt2::new(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2],
+        )
+    }
+}
+
+impl T3Mul<Vec2> for Mat3 {
+    fn mul(&self, v: &Vec2) -> Vec2 {
+        let m = self;
+        Vec2::new(m[0][0] * v.x + m[0][1] * v.y, m[1][0] * v.x + m[1][1] * v.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structure::SquareMat;
+
+    use super::*;
+
+    fn transform() -> Mat3 {
+        Transform3::new(
+            0., -2., 1., //
+            2., 0., 2.,
+        )
+    }
+
+    #[test]
+    fn new_places_translation_in_last_column() {
+        let m = transform();
+        assert_eq!(m.get_translation(), Vec2::new(1., 2.));
+        assert_eq!(m * Vec3::Z, Vec3::new(1., 2., 1.));
+        assert_eq!(m[2], Vec3::Z);
+    }
+
+    #[test]
+    fn from_columns_works() {
+        let m = <Mat3 as Transform3>::from_columns(
+            Vec2::new(0., 2.),
+            Vec2::new(-2., 0.),
+            Vec2::new(1., 2.),
+        );
+        assert_eq!(m, transform());
+    }
+
+    #[test]
+    fn sets_translation() {
+        let mut m = transform();
+        m.set_translation(Vec2::new(4., 5.));
+        assert_eq!(
+            Vec2::from(T3Mul::mul(&m, &Pt2::new(0., 0.))),
+            Vec2::new(4., 5.)
+        );
+    }
+
+    #[test]
+    fn inverts() {
+        let m = transform();
+        let inv = Transform3::invert(&m).unwrap();
+        assert_eq!(m * inv, Mat3::IDENTITY);
+        assert_eq!(inv, SquareMat::invert(&m).unwrap());
+    }
+
+    #[test]
+    fn transforms_points_and_vectors() {
+        let m = transform();
+        let p = T3Mul::mul(&m, &Pt2::new(1., 1.));
+        assert_eq!(Vec2::from(p), Vec2::new(-1., 4.));
+        assert_eq!(T3Mul::mul(&m, &Vec2::new(1., 1.)), Vec2::new(-2., 2.));
+        assert_eq!(T3Mul::mul(&m, &m), m * m);
+    }
+}
@@ -1,9 +1,21 @@
-use crate::{mat4::Mat4, plane::Plane, structure::EuclideanSpace, vec3::Vec3};
+use crate::{
+    mat3::Mat3,
+    mat4::Mat4,
+    plane::Plane,
+    precision::is_invertible,
+    pt3::Pt3,
+    structure::{EuclideanSpace, Mat, SquareMat, VecSpace},
+    vec3::Vec3,
+    vec4::Vec4,
+};
 
 pub trait Transform4
 where
     Self: Clone,
 {
+    /// Builds an affine matrix from the upper 3x4 part, given row by row.
+    /// `cNrM` is column `N` of row `M`; the translation is column 3 and the
+    /// bottom row is always `(0, 0, 0, 1)`.
     #[allow(clippy::too_many_arguments)]
     fn new(
         c0r0: f32,
@@ -20,6 +32,8 @@ where
         c3r2: f32,
     ) -> Self;
 
+    /// Builds an affine matrix from its basis columns and the translation `p`,
+    /// which ends up in the last column.
     fn from_columns(a: Vec3, b: Vec3, c: Vec3, p: Vec3) -> Self;
 
     fn get_translation(&self) -> Vec3;
@@ -28,6 +42,11 @@ where
 
     fn invert(&self) -> Option<Self>;
 
+    /// Inverse transpose of the linear part, which maps surface normals.
+    /// `None` when the linear part is singular.
+    fn normal_matrix(&self) -> Option<Mat3>;
+
+    /// Reflection across `plane`, with its offset in the last column.
     fn make_reflection(plane: &Plane) -> Self;
 }
 
@@ -54,39 +73,44 @@ impl Transform4 for Mat4 {
         c3r2: f32,
     ) -> Self {
         Self::from_columns(
-            [c0r0, c0r1, c0r2, 0.].into(),
-            [c1r0, c1r1, c1r2, 0.].into(),
-            [c2r0, c2r1, c2r2, 0.].into(),
-            [c3r0, c3r1, c3r2, 1.].into(),
+            [c0r0, c1r0, c2r0, c3r0].into(),
+            [c0r1, c1r1, c2r1, c3r1].into(),
+            [c0r2, c1r2, c2r2, c3r2].into(),
+            Vec4::W,
         )
     }
 
     fn from_columns(a: Vec3, b: Vec3, c: Vec3, p: Vec3) -> Self {
-        Self::from_columns(a.extend(0.), b.extend(0.), c.extend(0.), p.extend(1.))
+        Transform4::new(
+            a.x, b.x, c.x, p.x, //
+            a.y, b.y, c.y, p.y, //
+            a.z, b.z, c.z, p.z,
+        )
     }
 
     fn get_translation(&self) -> Vec3 {
-        self.d.xyz()
+        Vec3::new(self[0][3], self[1][3], self[2][3])
     }
 
     fn set_translation(&mut self, t: Vec3) {
-        self.d.x = t.x;
-        self.d.y = t.y;
-        self.d.z = t.z;
+        self[0][3] = t.x;
+        self[1][3] = t.y;
+        self[2][3] = t.z;
     }
 
     fn invert(&self) -> Option<Self> {
-        let a: Vec3 = self.a.into();
-        let b: Vec3 = self.b.into();
-        let c: Vec3 = self.c.into();
-        let d: Vec3 = self.d.into();
+        let m = self;
+        let a = Vec3::new(m[0][0], m[1][0], m[2][0]);
+        let b = Vec3::new(m[0][1], m[1][1], m[2][1]);
+        let c = Vec3::new(m[0][2], m[1][2], m[2][2]);
+        let d = self.get_translation();
 
         let mut s = a.cross(&b);
         let mut t = c.cross(&d);
 
         let det = s.dot(&c);
 
-        if det != 0. {
+        if is_invertible(det, &[a.norm(), b.norm(), c.norm()]) {
             let inv_det = 1. / det;
             s *= inv_det;
             t *= inv_det;
@@ -96,17 +120,29 @@ impl Transform4 for Mat4 {
             let r0 = b.cross(&v);
             let r1 = v.cross(&a);
 
-            Some(Self::from_columns(
-                r0.extend(-b.dot(&t)),
-                r1.extend(a.dot(&t)),
-                s.extend(-d.dot(&s)),
-                [0., 0., 0., 1.].into(),
+            Some(Transform4::new(
+                r0.x,
+                r0.y,
+                r0.z,
+                -b.dot(&t), //
+                r1.x,
+                r1.y,
+                r1.z,
+                a.dot(&t), //
+                s.x,
+                s.y,
+                s.z,
+                -d.dot(&s),
             ))
         } else {
             None
         }
     }
 
+    fn normal_matrix(&self) -> Option<Mat3> {
+        Some(Mat3::from(self).invert()?.transpose())
+    }
+
     fn make_reflection(plane: &Plane) -> Self {
         let x = plane.x * -2.;
         let y = plane.y * -2.;
@@ -151,6 +187,31 @@ impl T4Mul for Mat4 {
     }
 }
 
+impl T4Mul<Pt3> for Mat4 {
+    fn mul(&self, p: &Pt3) -> Pt3 {
+        // Warning: the bottom row is ignored, there is no perspective divide
+        let h = self;
+        let Pt3 { x, y, z } = p;
+        Pt3::new(
+            h[0][0] * x + h[0][1] * y + h[0][2] * z + h[0][3],
+            h[1][0] * x + h[1][1] * y + h[1][2] * z + h[1][3],
+            h[2][0] * x + h[2][1] * y + h[2][2] * z + h[2][3],
+        )
+    }
+}
+
+impl T4Mul<Vec3> for Mat4 {
+    fn mul(&self, v: &Vec3) -> Vec3 {
+        let h = self;
+        let Vec3 { x, y, z } = v;
+        Vec3::new(
+            h[0][0] * x + h[0][1] * y + h[0][2] * z,
+            h[1][0] * x + h[1][1] * y + h[1][2] * z,
+            h[2][0] * x + h[2][1] * y + h[2][2] * z,
+        )
+    }
+}
+
 impl T4Mul<Plane> for Mat4 {
     fn mul(&self, f: &Plane) -> Plane {
         let h = self;
@@ -163,3 +224,96 @@ impl T4Mul<Plane> for Mat4 {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_approx_eq, structure::SquareMat, vec4::Vec4};
+
+    use super::*;
+
+    fn transform() -> Mat4 {
+        Transform4::new(
+            2., 0., 0., 1., //
+            0., 0., -3., 2., //
+            0., 1., 0., 3.,
+        )
+    }
+
+    #[test]
+    fn new_places_translation_in_last_column() {
+        let m = transform();
+        assert_eq!(m.get_translation(), Vec3::new(1., 2., 3.));
+        assert_eq!(m * Vec4::W, Vec4::new(1., 2., 3., 1.));
+        assert_eq!(m[3], Vec4::W);
+    }
+
+    #[test]
+    fn from_columns_works() {
+        let m = <Mat4 as Transform4>::from_columns(
+            Vec3::new(2., 0., 0.),
+            Vec3::new(0., 0., 1.),
+            Vec3::new(0., -3., 0.),
+            Vec3::new(1., 2., 3.),
+        );
+        assert_eq!(m, transform());
+    }
+
+    #[test]
+    fn sets_translation() {
+        let mut m = transform();
+        m.set_translation(Vec3::new(4., 5., 6.));
+        assert_eq!(m * Vec4::W, Vec4::new(4., 5., 6., 1.));
+    }
+
+    #[test]
+    fn inverts() {
+        let m = transform();
+        let inv = Transform4::invert(&m).unwrap();
+        assert_eq!(m * inv, Mat4::IDENTITY);
+        assert_eq!(inv, SquareMat::invert(&m).unwrap());
+    }
+
+    #[test]
+    fn t4_mul_matches_mul() {
+        let m = transform();
+        let n = Mat4::make_reflection(&Plane::new(0., 1., 0., -1.));
+        assert_eq!(T4Mul::mul(&m, &n), m * n);
+    }
+
+    #[test]
+    fn maps_points_and_vectors() {
+        let m = transform();
+        let p = T4Mul::mul(&m, &Pt3::new(1., 1., 1.));
+        assert_approx_eq!(p, Pt3::new(3., -1., 4.));
+        assert_eq!(
+            T4Mul::mul(&m, &Vec3::new(1., 1., 1.)),
+            Vec3::new(2., -3., 1.)
+        );
+        // Points move with the translation, vectors do not
+        let q = T4Mul::mul(&m, &Pt3::new(0., 0., 0.));
+        assert_approx_eq!(q, Pt3::from(m.get_translation()));
+        assert_eq!(T4Mul::mul(&m, &Vec3::ZERO), Vec3::ZERO);
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular() {
+        let m = transform();
+        let n = m.normal_matrix().unwrap();
+        // Tangents of the plane x + y = 0 stay perpendicular to its normal
+        let normal = Vec3::new(1., 1., 0.);
+        for tangent in [Vec3::new(1., -1., 0.), Vec3::Z] {
+            let t = T4Mul::mul(&m, &tangent);
+            assert!((n * normal).dot(&t).abs() < 1e-6);
+        }
+        assert_eq!(Mat4::IDENTITY.normal_matrix().unwrap(), Mat3::IDENTITY);
+        assert!(Mat4::from(Mat3::from_diagonal(Vec3::new(1., 0., 1.)))
+            .normal_matrix()
+            .is_none());
+    }
+
+    #[test]
+    fn reflects_through_plane() {
+        let m = Mat4::make_reflection(&Plane::new(0., 1., 0., -1.));
+        assert_eq!(m * Vec4::new(3., 4., 5., 1.), Vec4::new(3., -2., 5., 1.));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    aabb::Aabb,
+    pt3::Pt3,
+    structure::{EuclideanSpace, VecSpace},
+    vec3::Vec3,
+};
+
+/// Triangle with counter-clockwise winding `a`, `b`, `c` around `normal`.
+#[derive(Clone, Copy, Debug)]
+pub struct Triangle {
+    pub a: Pt3,
+    pub b: Pt3,
+    pub c: Pt3,
+}
+
+impl Triangle {
+    pub fn new(a: Pt3, b: Pt3, c: Pt3) -> Self {
+        Self { a, b, c }
+    }
+
+    /// Unnormalized normal, twice the area long.
+    pub fn scaled_normal(&self) -> Vec3 {
+        Vec3::from(self.b - self.a).cross(&(self.c - self.a).into())
+    }
+
+    pub fn normal(&self) -> Vec3 {
+        self.scaled_normal().normalize()
+    }
+
+    pub fn area(&self) -> f32 {
+        self.scaled_normal().norm() * 0.5
+    }
+
+    pub fn centroid(&self) -> Pt3 {
+        (self.a + self.b + self.c) / 3.
+    }
+
+    pub fn vertices(&self) -> [Pt3; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        Aabb::new(self.a, self.a).include(&self.b).include(&self.c)
+    }
+
+    /// Barycentric weights of `a`, `b` and `c` for the projection of `p`
+    /// onto the triangle's plane. `None` for degenerate triangles.
+    pub fn barycentric(&self, p: &Pt3) -> Option<Vec3> {
+        let n = self.scaled_normal();
+        let nn = n.norm2();
+        if nn <= 0. {
+            return None;
+        }
+        let pa = Vec3::from(self.a - p);
+        let pb = Vec3::from(self.b - p);
+        let pc = Vec3::from(self.c - p);
+        let u = pb.cross(&pc).dot(&n) / nn;
+        let v = pc.cross(&pa).dot(&n) / nn;
+        Some(Vec3::new(u, v, 1. - u - v))
+    }
+
+    /// Point of the triangle closest to `p`, see Ericson, RTCD section 5.1.5.
+    pub fn closest_point(&self, p: &Pt3) -> Pt3 {
+        let w = self.closest_barycentric(p);
+        self.a * w.x + self.b * w.y + self.c * w.z
+    }
+
+    /// Barycentric weights of `a`, `b` and `c` for the point of the triangle
+    /// closest to `p`.
+    pub(crate) fn closest_barycentric(&self, p: &Pt3) -> Vec3 {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = Vec3::from(b - a);
+        let ac = Vec3::from(c - a);
+        let ap = Vec3::from(*p - a);
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= 0. && d2 <= 0. {
+            return Vec3::X;
+        }
+
+        let bp = Vec3::from(*p - b);
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= 0. && d4 <= d3 {
+            return Vec3::Y;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0. && d1 >= 0. && d3 <= 0. {
+            let v = d1 / (d1 - d3);
+            return Vec3::new(1. - v, v, 0.);
+        }
+
+        let cp = Vec3::from(*p - c);
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= 0. && d5 <= d6 {
+            return Vec3::Z;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0. && d2 >= 0. && d6 <= 0. {
+            let w = d2 / (d2 - d6);
+            return Vec3::new(1. - w, 0., w);
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0. && d4 - d3 >= 0. && d5 - d6 >= 0. {
+            let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return Vec3::new(0., 1. - w, w);
+        }
+
+        let denom = 1. / (va + vb + vc);
+        let v = vb * denom;
+        let w = vc * denom;
+        Vec3::new(1. - v - w, v, w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use crate::{assert_approx_eq, precision::PRECISION};
+
+    use super::*;
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Pt3::new(0., 0., 0.),
+            Pt3::new(2., 0., 0.),
+            Pt3::new(0., 2., 0.),
+        )
+    }
+
+    #[test]
+    fn measures() {
+        let t = triangle();
+        assert_eq!(t.normal(), Vec3::Z);
+        assert!(approx_eq!(f32, t.area(), 2., PRECISION));
+        assert_approx_eq!(t.centroid(), Pt3::new(2. / 3., 2. / 3., 0.));
+    }
+
+    #[test]
+    fn computes_barycentrics() {
+        let t = triangle();
+        assert_eq!(t.barycentric(&Pt3::new(0., 0., 5.)).unwrap(), Vec3::X);
+        assert_eq!(
+            t.barycentric(&Pt3::new(0.5, 0.5, 0.)).unwrap(),
+            Vec3::new(0.5, 0.25, 0.25)
+        );
+        let flat = Triangle::new(t.a, t.a, t.b);
+        assert!(flat.barycentric(&t.c).is_none());
+    }
+
+    #[test]
+    fn finds_closest_point() {
+        let t = triangle();
+        let closest = |x, y, z| Vec3::from(t.closest_point(&Pt3::new(x, y, z)));
+        assert_eq!(closest(0.5, 0.5, 3.), Vec3::new(0.5, 0.5, 0.));
+        assert_eq!(closest(-1., -1., 0.), Vec3::ZERO);
+        assert_eq!(closest(3., -1., 0.), Vec3::new(2., 0., 0.));
+        assert_eq!(closest(1., -1., 0.), Vec3::new(1., 0., 0.));
+        assert_eq!(closest(2., 2., 0.), Vec3::new(1., 1., 0.));
+    }
+}
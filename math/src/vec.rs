@@ -1,9 +1,25 @@
 use std::ops;
 
-use crate::{impl_algebraic_ops, impl_conversions, impl_index_ops, vec3::Vec3, vec4::Vec4};
+use crate::{
+    dvec3::DVec3, dvec4::DVec4, impl_algebraic_ops, impl_componentwise_ops, impl_conversions,
+    impl_index_ops, vec2::Vec2, vec3::Vec3, vec4::Vec4,
+};
 
+impl_algebraic_ops!(Vec2 { x, y }, 2);
 impl_algebraic_ops!(Vec3 { x, y, z }, 3);
 impl_algebraic_ops!(Vec4 { x, y, z, w }, 4);
+impl_algebraic_ops!(DVec3 { x, y, z }, 3, f64);
+impl_algebraic_ops!(DVec4 { x, y, z, w }, 4, f64);
+
+impl_componentwise_ops!(Vec3 { x, y, z }, 3);
+impl_componentwise_ops!(Vec4 { x, y, z, w }, 4);
+
+impl_conversions!(Vec2 => [f32; 2], |v: &Vec2| {
+    [v.x, v.y]
+});
+impl_conversions!([f32; 2] => Vec2, |a: &[f32; 2]| {
+    Vec2::new(a[0], a[1])
+});
 
 impl_conversions!(Vec3 => [f32; 3], |v: &Vec3| {
     [v.x, v.y, v.z]
@@ -23,5 +39,38 @@ impl_conversions!(Vec4 => Vec3, |v4: &Vec4| {
     Vec3::new(v4.x, v4.y, v4.z)
 });
 
+impl_conversions!(Vec3 => Vec2, |v3: &Vec3| {
+    Vec2::new(v3.x, v3.y)
+});
+
+impl_conversions!(DVec3 => [f64; 3], |v: &DVec3| {
+    [v.x, v.y, v.z]
+});
+impl_conversions!([f64; 3] => DVec3, |a: &[f64; 3]| {
+    DVec3::new(a[0], a[1], a[2])
+});
+
+impl_conversions!(DVec4 => [f64; 4], |v: &DVec4| {
+    [v.x, v.y, v.z, v.w]
+});
+impl_conversions!([f64; 4] => DVec4, |a: &[f64; 4]| {
+    DVec4::new(a[0], a[1], a[2], a[3])
+});
+
+impl_conversions!(DVec4 => DVec3, |v4: &DVec4| {
+    DVec3::new(v4.x, v4.y, v4.z)
+});
+
+// Widening is lossless, narrowing goes through `as_f32`
+impl_conversions!(Vec3 => DVec3, |v: &Vec3| {
+    DVec3::new(v.x.into(), v.y.into(), v.z.into())
+});
+impl_conversions!(Vec4 => DVec4, |v: &Vec4| {
+    DVec4::new(v.x.into(), v.y.into(), v.z.into(), v.w.into())
+});
+
+impl_index_ops!(Vec2 { 0 => x, 1 => y } => f32);
 impl_index_ops!(Vec3 { 0 => x, 1 => y, 2 => z } => f32);
 impl_index_ops!(Vec4 { 0 => x, 1 => y, 2 => z, 3 => w } => f32);
+impl_index_ops!(DVec3 { 0 => x, 1 => y, 2 => z } => f64);
+impl_index_ops!(DVec4 { 0 => x, 1 => y, 2 => z, 3 => w } => f64);
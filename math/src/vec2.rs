@@ -0,0 +1,108 @@
+use crate::{
+    structure::{EuclideanSpace, ProjectionSpace, VecSpace},
+    vec3::Vec3,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const ZERO: Self = Self { x: 0., y: 0. };
+
+    pub const X: Self = Self { x: 1., y: 0. };
+
+    pub const Y: Self = Self { x: 0., y: 1. };
+
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// The vector rotated a quarter turn counter-clockwise.
+    pub fn perp(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// The z component of the 3D cross product, `|a| |b| sin(t)`.
+    pub fn perp_dot(&self, rhs: &Self) -> f32 {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    pub fn extend(&self, z: f32) -> Vec3 {
+        Vec3::new(self.x, self.y, z)
+    }
+}
+
+impl EuclideanSpace<Self> for Vec2 {
+    type Scalar = f32;
+
+    fn dot(&self, other: &Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+impl VecSpace for Vec2 {}
+
+impl ProjectionSpace<Vec2> for Vec2 {
+    fn project(&self, v: &Vec2) -> Vec2 {
+        self * (v.dot(self) / self.norm2())
+    }
+
+    fn reject(&self, v: &Vec2) -> Vec2 {
+        v - self.project(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts() {
+        let v = Vec2::new(1., 2.);
+        assert_eq!(v, [1., 2.].into());
+        assert_eq!([1., 2.], <Vec2 as Into<[f32; 2]>>::into(v));
+        assert_eq!(v[0], 1.);
+        assert_eq!(v[1], 2.);
+    }
+
+    #[test]
+    fn algebraic_ops_work() {
+        let mut v = Vec2::new(1., 2.) + Vec2::new(3., 4.);
+        assert_eq!(v, Vec2::new(4., 6.));
+        v -= Vec2::new(1., 1.);
+        assert_eq!(v * 2., Vec2::new(6., 10.));
+        assert_eq!(-v / 2., Vec2::new(-1.5, -2.5));
+        assert_eq!(v.norm2(), 34.);
+    }
+
+    #[test]
+    fn perp_works() {
+        let v = Vec2::new(3., 1.);
+        assert_eq!(v.perp(), Vec2::new(-1., 3.));
+        assert_eq!(v.dot(&v.perp()), 0.);
+        assert_eq!(Vec2::X.perp_dot(&Vec2::Y), 1.);
+        assert_eq!(
+            v.perp_dot(&Vec2::new(2., 5.)),
+            v.extend(0.).cross(&Vec3::new(2., 5., 0.)).z
+        );
+    }
+
+    #[test]
+    fn extends_and_truncates() {
+        let v = Vec2::new(1., 2.);
+        assert_eq!(v.extend(3.), Vec3::new(1., 2., 3.));
+        assert_eq!(v.extend(3.).truncate(), v);
+        assert_eq!(Vec2::from(Vec3::new(1., 2., 3.)), v);
+    }
+
+    #[test]
+    fn project_works() {
+        let v = Vec2::new(1., 1.);
+        assert_eq!(Vec2::X.project(&v), Vec2::X);
+        assert_eq!(Vec2::X.reject(&v), Vec2::Y);
+    }
+}
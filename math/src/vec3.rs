@@ -1,9 +1,11 @@
 use crate::{
     structure::{EuclideanSpace, ProjectionSpace, VecSpace},
+    vec2::Vec2,
     vec4::Vec4,
 };
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "gpu", repr(C))]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -50,9 +52,15 @@ impl Vec3 {
     pub fn extend(&self, w: f32) -> Vec4 {
         Vec4::new(self.x, self.y, self.z, w)
     }
+
+    pub fn truncate(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
 }
 
 impl EuclideanSpace<Self> for Vec3 {
+    type Scalar = f32;
+
     fn dot(&self, other: &Self) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
@@ -74,7 +82,7 @@ impl ProjectionSpace<Vec3> for Vec3 {
 mod tests {
     use float_cmp::approx_eq;
 
-    use crate::precision::PRECISION;
+    use crate::{precision::PRECISION, pt3::Pt3};
 
     use super::*;
 
@@ -185,6 +193,53 @@ mod tests {
         assert_eq!(vec3, Vec3::new(0.5, 1., 1.5));
     }
 
+    #[test]
+    fn componentwise_ops_work() {
+        let a = Vec3::new(1., -2., 3.5);
+        let b = Vec3::new(2., 4., -1.);
+        assert_eq!(a * b, Vec3::new(2., -8., -3.5));
+        assert_eq!(a / b, Vec3::new(0.5, -0.5, -3.5));
+        let mut c = a;
+        c *= b;
+        c /= b;
+        assert_eq!(c, a);
+
+        assert_eq!(a.min(&b), Vec3::new(1., -2., -1.));
+        assert_eq!(a.max(&b), Vec3::new(2., 4., 3.5));
+        assert_eq!(
+            a.clamp(&Vec3::ZERO, &Vec3::new(1., 1., 1.)),
+            Vec3::new(1., 0., 1.)
+        );
+        assert_eq!(a.abs(), Vec3::new(1., 2., 3.5));
+        assert_eq!(a.floor(), Vec3::new(1., -2., 3.));
+        assert_eq!(a.ceil(), Vec3::new(1., -2., 4.));
+        assert_eq!(Vec3::new(0.4, -0.6, 2.5).round(), Vec3::new(0., -1., 3.));
+        assert_eq!(a.lerp(&b, 0.5), Vec3::new(1.5, 1., 1.25));
+        assert_eq!(a.min_element(), -2.);
+        assert_eq!(a.max_element(), 3.5);
+        assert_eq!(
+            Vec3::select([true, false, true], &a, &b),
+            Vec3::new(1., 4., 3.5)
+        );
+    }
+
+    #[test]
+    fn swizzles() {
+        let v = Vec3::new(1., 2., 3.);
+        assert_eq!(v.xzy(), Vec3::new(1., 3., 2.));
+        assert_eq!(v.zx(), Vec2::new(3., 1.));
+        assert_eq!(v.xxyy(), Vec4::new(1., 1., 2., 2.));
+        let w = Vec4::new(1., 2., 3., 4.);
+        assert_eq!(w.wzyx(), Vec4::new(4., 3., 2., 1.));
+        assert_eq!(w.xyz(), v);
+        assert_eq!(
+            Vec3::from(Pt3::new(1., 2., 3.).zyx()),
+            Vec3::new(3., 2., 1.)
+        );
+        let p = Pt3::new(1., 2., 3.).yz();
+        assert_eq!((p.x, p.y), (2., 3.));
+    }
+
     #[test]
     fn sqr_magnitude_works() {
         assert_eq!(Vec3::new(1., 2., 3.).norm2(), 14.);
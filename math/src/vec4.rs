@@ -1,9 +1,11 @@
 use crate::{
+    simd,
     structure::{EuclideanSpace, VecSpace},
-    vec3::Vec3,
 };
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(all(feature = "gpu", not(feature = "simd")), repr(C))]
+#[cfg_attr(feature = "simd", repr(C, align(16)))]
 pub struct Vec4 {
     pub x: f32,
     pub y: f32,
@@ -50,16 +52,17 @@ impl Vec4 {
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
         Self { x, y, z, w }
     }
-
-    pub fn xyz(&self) -> Vec3 {
-        Vec3::new(self.x, self.y, self.z)
-    }
 }
 
 impl VecSpace for Vec4 {}
 
 impl EuclideanSpace<Self> for Vec4 {
+    type Scalar = f32;
+
     fn dot(&self, other: &Self) -> f32 {
+        if cfg!(feature = "simd") {
+            return simd::vec4_dot(self, other);
+        }
         self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
     }
 }
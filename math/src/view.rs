@@ -0,0 +1,189 @@
+use crate::{
+    mat4::Mat4,
+    pt3::Pt3,
+    structure::{EuclideanSpace, VecSpace},
+    transform4::Transform4,
+    vec3::Vec3,
+};
+
+// `_rh` view matrices put the camera in a right-handed, y-up space looking
+// down -z, `_lh` view matrices in a left-handed, y-up space looking down +z,
+// matching the `_rh` and `_lh` projections.
+
+#[derive(Clone, Copy, Debug)]
+pub struct CameraBasis {
+    pub eye: Pt3,
+    pub right: Vec3,
+    pub up: Vec3,
+    pub forward: Vec3,
+}
+
+fn view(eye: &Pt3, right: Vec3, up: Vec3, back: Vec3) -> Mat4 {
+    let e = Vec3::from(eye);
+    Transform4::new(
+        right.x,
+        right.y,
+        right.z,
+        -right.dot(&e),
+        up.x,
+        up.y,
+        up.z,
+        -up.dot(&e),
+        back.x,
+        back.y,
+        back.z,
+        -back.dot(&e),
+    )
+}
+
+impl Mat4 {
+    pub fn look_at_rh(eye: Pt3, target: Pt3, up: Vec3) -> Self {
+        Self::look_to_rh(eye, (target - eye).into(), up)
+    }
+
+    pub fn look_at_lh(eye: Pt3, target: Pt3, up: Vec3) -> Self {
+        Self::look_to_lh(eye, (target - eye).into(), up)
+    }
+
+    pub fn look_to_rh(eye: Pt3, dir: Vec3, up: Vec3) -> Self {
+        // Warning: `dir` must not be parallel to `up`
+        let f = dir.normalize();
+        let r = f.cross(&up).normalize();
+        let u = r.cross(&f);
+        view(&eye, r, u, -f)
+    }
+
+    pub fn look_to_lh(eye: Pt3, dir: Vec3, up: Vec3) -> Self {
+        // Warning: `dir` must not be parallel to `up`
+        let f = dir.normalize();
+        let r = up.cross(&f).normalize();
+        let u = f.cross(&r);
+        view(&eye, r, u, f)
+    }
+
+    /// Recovers the world-space camera position and axes from a view matrix
+    /// built with the right-handed convention. Scale and shear in the view
+    /// matrix are tolerated; the returned axes are normalized.
+    pub fn camera_basis_rh(&self) -> Option<CameraBasis> {
+        let basis = self.camera_basis_lh()?;
+        Some(CameraBasis {
+            forward: -basis.forward,
+            ..basis
+        })
+    }
+
+    pub fn camera_basis_lh(&self) -> Option<CameraBasis> {
+        // The inverse maps camera space back to world space, so its columns
+        // are the camera axes and its translation is the eye
+        let inv = Transform4::invert(self)?;
+        let column = |j: usize| Vec3::new(inv[0][j], inv[1][j], inv[2][j]).normalize();
+        Some(CameraBasis {
+            eye: inv.get_translation().into(),
+            right: column(0),
+            up: column(1),
+            forward: column(2),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use crate::{assert_approx_eq, precision::PRECISION, structure::SquareMat, vec4::Vec4};
+
+    use super::*;
+
+    fn transform(m: &Mat4, p: Pt3) -> Pt3 {
+        (m * Vec3::from(p).extend(1.)).xyz().into()
+    }
+
+    #[test]
+    fn look_at_rh_puts_target_on_negative_z() {
+        let eye = Pt3::new(1., 2., 3.);
+        let m = Mat4::look_at_rh(eye, Pt3::new(1., 2., -7.), Vec3::Y);
+        assert_approx_eq!(transform(&m, eye), Pt3::new(0., 0., 0.));
+        assert_eq!(
+            Vec3::from(transform(&m, Pt3::new(1., 2., -7.))),
+            Vec3::new(0., 0., -10.)
+        );
+        assert_eq!(
+            Vec3::from(transform(&m, Pt3::new(2., 3., 3.))),
+            Vec3::new(1., 1., 0.)
+        );
+    }
+
+    #[test]
+    fn look_at_lh_puts_target_on_positive_z() {
+        let eye = Pt3::new(1., 2., 3.);
+        let m = Mat4::look_at_lh(eye, Pt3::new(1., 2., -7.), Vec3::Y);
+        assert_eq!(
+            Vec3::from(transform(&m, Pt3::new(1., 2., -7.))),
+            Vec3::new(0., 0., 10.)
+        );
+        // Looking down world -z with a left-handed frame flips right to -x
+        assert_eq!(
+            Vec3::from(transform(&m, Pt3::new(2., 3., 3.))),
+            Vec3::new(-1., 1., 0.)
+        );
+    }
+
+    #[test]
+    fn look_to_matches_look_at() {
+        let eye = Pt3::new(-4., 1., 2.);
+        let target = Pt3::new(3., -2., 5.);
+        let dir = Vec3::from(target - eye);
+        assert_eq!(
+            Mat4::look_to_rh(eye, dir, Vec3::Y),
+            Mat4::look_at_rh(eye, target, Vec3::Y)
+        );
+        assert_eq!(
+            Mat4::look_to_lh(eye, dir * 3., Vec3::Y),
+            Mat4::look_at_lh(eye, target, Vec3::Y)
+        );
+    }
+
+    #[test]
+    fn view_is_rigid() {
+        let m = Mat4::look_at_rh(Pt3::new(-4., 1., 2.), Pt3::new(3., -2., 5.), Vec3::Y);
+        assert!(approx_eq!(f32, m.determinant(), 1., PRECISION));
+        assert_eq!(m[3], Vec4::W);
+    }
+
+    #[test]
+    fn extracts_camera_basis_rh() {
+        let eye = Pt3::new(-4., 1., 2.);
+        let target = Pt3::new(3., -2., 5.);
+        let m = Mat4::look_at_rh(eye, target, Vec3::Y);
+        let basis = m.camera_basis_rh().unwrap();
+        let forward = Vec3::from(target - eye).normalize();
+        assert_approx_eq!(basis.eye, eye);
+        assert_eq!(basis.forward, forward);
+        assert_eq!(basis.right, forward.cross(&Vec3::Y).normalize());
+        assert_eq!(basis.up, basis.right.cross(&forward));
+    }
+
+    #[test]
+    fn extracts_camera_basis_lh() {
+        let eye = Pt3::new(-4., 1., 2.);
+        let target = Pt3::new(3., -2., 5.);
+        let m = Mat4::look_at_lh(eye, target, Vec3::Y);
+        let basis = m.camera_basis_lh().unwrap();
+        let forward = Vec3::from(target - eye).normalize();
+        assert_approx_eq!(basis.eye, eye);
+        assert_eq!(basis.forward, forward);
+        assert_eq!(basis.right, Vec3::Y.cross(&forward).normalize());
+        assert!(basis.up.dot(&Vec3::Y) > 0.);
+    }
+
+    #[test]
+    fn extracts_camera_basis_from_scaled_view() {
+        let eye = Pt3::new(0., 5., 0.);
+        let m =
+            Mat4::look_to_rh(eye, -Vec3::Z, Vec3::Y) * Mat4::from_diagonal([2., 2., 2., 1.].into());
+        let basis = m.camera_basis_rh().unwrap();
+        assert_eq!(basis.forward, -Vec3::Z);
+        assert_eq!(basis.right, Vec3::X);
+        assert_eq!(basis.up, Vec3::Y);
+    }
+}
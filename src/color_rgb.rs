@@ -1,4 +1,4 @@
-use rge_math::{impl_algebraic_ops, impl_op, impl_op_assign};
+use rge_math::{impl_algebraic_ops, impl_componentwise_ops};
 use std::ops;
 
 #[derive(Clone, Copy)]
@@ -21,11 +21,4 @@ impl ColorRGB {
 }
 
 impl_algebraic_ops!(ColorRGB { r, g, b, a }, 4);
-
-impl_op!(ColorRGB : ColorRGB, ops::Mul { fn mul |lhs: &ColorRGB, rhs: &ColorRGB| {
-    ColorRGB::new(lhs.r * rhs.r, lhs.g * rhs.g, lhs.b * rhs.b, Some(lhs.a * rhs.a))
-}});
-
-impl_op_assign!(ColorRGB, ColorRGB, ops::MulAssign { fn mul_assign |lhs: &mut ColorRGB, rhs: &ColorRGB| {
-    *lhs = *lhs * rhs;
-}});
+impl_componentwise_ops!(ColorRGB { r, g, b, a }, 4);